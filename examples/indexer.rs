@@ -38,7 +38,6 @@ async fn main() -> Result<()> {
         .init();
 
     let client = QdrantInstance::start(None)?;
-    client.delete_collection(COLLECTION_NAME).await?;
 
     let params = VectorParams {
         size: NonZeroU64::new(OPENAI_EMBEDDING_DIM).unwrap(),
@@ -48,7 +47,7 @@ async fn main() -> Result<()> {
         on_disk: Some(true),
     };
     client
-        .create_collection(COLLECTION_NAME, params.into())
+        .recreate_collection(COLLECTION_NAME, params.into())
         .await?;
 
     let filename = "./fixtures/wikipedia.zip";
@@ -1,6 +1,5 @@
 use anyhow::Result;
 use qdrant_lib::{QdrantError, QdrantInstance};
-use storage::content_manager::errors::StorageError;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -13,8 +12,8 @@ async fn main() -> Result<()> {
         .await
     {
         Ok(v) => println!("Collection created: {:?}", v),
-        Err(QdrantError::Storage(StorageError::BadInput { description })) => {
-            println!("{description}");
+        Err(e @ QdrantError::CollectionAlreadyExists { .. }) => {
+            println!("{e}");
         }
         Err(e) => panic!("Unexpected error: {:?}", e),
     }
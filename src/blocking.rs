@@ -0,0 +1,151 @@
+use crate::{QdrantClient, QdrantError, QdrantInstance};
+use collection::operations::{
+    point_ops::{PointStruct, PointsSelector},
+    types::{
+        CollectionInfo, PointRequest, RecommendRequest, Record, ScrollRequest, ScrollResult,
+        SearchRequest, UpdateResult, VectorsConfig,
+    },
+};
+use segment::types::{Filter, ScoredPoint};
+use std::{future::Future, sync::Arc};
+use storage::content_manager::collection_meta_ops::CreateCollection;
+use tokio::runtime::{Builder, Runtime};
+
+/// Synchronous wrapper of [`QdrantClient`], for callers without an async runtime.
+///
+/// It owns a current-thread runtime to drive the requests, so the methods must not be
+/// called from within an async context. The full async client is available through
+/// [`BlockingQdrantClient::inner`] for anything not mirrored here.
+///
+/// ```ignore
+/// let client = BlockingQdrantClient::start(None)?;
+/// client.create_collection("docs", vectors_config)?;
+/// let points = client.search_points("docs", request)?;
+/// ```
+#[derive(Debug)]
+pub struct BlockingQdrantClient {
+    inner: Arc<QdrantClient>,
+    rt: Runtime,
+}
+
+impl BlockingQdrantClient {
+    /// Start an instance, see [`QdrantInstance::start`].
+    pub fn start(config_path: Option<String>) -> Result<Self, QdrantError> {
+        Self::new(QdrantInstance::start(config_path)?)
+    }
+
+    /// Wrap an already started client.
+    pub fn new(inner: Arc<QdrantClient>) -> Result<Self, QdrantError> {
+        let rt = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { inner, rt })
+    }
+
+    /// the wrapped async client
+    pub fn inner(&self) -> &Arc<QdrantClient> {
+        &self.inner
+    }
+
+    /// create a collection with the default HNSW, optimizer and WAL configs
+    pub fn create_collection(
+        &self,
+        name: impl Into<String>,
+        config: VectorsConfig,
+    ) -> Result<bool, QdrantError> {
+        self.block_on(self.inner.create_collection(name, config))
+    }
+
+    /// create a collection with the full set of options
+    pub fn create_collection_with(
+        &self,
+        name: impl Into<String>,
+        data: CreateCollection,
+    ) -> Result<bool, QdrantError> {
+        self.block_on(self.inner.create_collection_with(name, data))
+    }
+
+    /// names of all collections
+    pub fn list_collections(&self) -> Result<Vec<String>, QdrantError> {
+        self.block_on(self.inner.list_collections())
+    }
+
+    /// info of the collection, `None` if it doesn't exist
+    pub fn get_collection(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<Option<CollectionInfo>, QdrantError> {
+        self.block_on(self.inner.get_collection(name))
+    }
+
+    /// delete the collection, its files are removed in the background
+    pub fn delete_collection(&self, name: impl Into<String>) -> Result<bool, QdrantError> {
+        self.block_on(self.inner.delete_collection(name))
+    }
+
+    /// retrieve points by id
+    pub fn get_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: PointRequest,
+    ) -> Result<Vec<Record>, QdrantError> {
+        self.block_on(self.inner.get_points(collection_name, data))
+    }
+
+    /// insert or replace points, checked against the collection config first
+    pub fn upsert_points(
+        &self,
+        collection_name: impl Into<String>,
+        points: Vec<PointStruct>,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.block_on(self.inner.upsert_points(collection_name, points))
+    }
+
+    /// delete points by id or filter
+    pub fn delete_points(
+        &self,
+        collection_name: impl Into<String>,
+        points: PointsSelector,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.block_on(self.inner.delete_points(collection_name, points))
+    }
+
+    /// number of points matching the filter, approximate unless `exact`
+    pub fn count_points(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+        exact: bool,
+    ) -> Result<usize, QdrantError> {
+        self.block_on(self.inner.count_points(collection_name, filter, exact))
+    }
+
+    /// one page of points in id order
+    pub fn scroll_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: ScrollRequest,
+    ) -> Result<ScrollResult, QdrantError> {
+        self.block_on(self.inner.scroll_points(collection_name, data))
+    }
+
+    /// nearest neighbours of a vector
+    pub fn search_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchRequest,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        self.block_on(self.inner.search_points(collection_name, data))
+    }
+
+    /// points close to the positive examples and far from the negative ones
+    pub fn recommend_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: RecommendRequest,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        self.block_on(self.inner.recommend_points(collection_name, data))
+    }
+
+    fn block_on<F: Future>(&self, fut: F) -> F::Output {
+        self.rt.block_on(fut)
+    }
+}
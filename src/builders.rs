@@ -0,0 +1,575 @@
+use crate::QdrantError;
+use collection::config::ShardingMethod;
+use collection::operations::{
+    config_diff::{HnswConfigDiff, OptimizersConfigDiff, WalConfigDiff},
+    point_ops::PointStruct,
+    shard_key_selector::ShardKeySelector,
+    types::{
+        LookupLocation, RecommendExample, RecommendRequest, RecommendRequestInternal,
+        RecommendStrategy, ScrollRequest, ScrollRequestInternal, SearchRequest,
+        SearchRequestInternal, UsingVector, VectorsConfig,
+    },
+};
+use segment::{
+    data_types::vectors::{NamedVectorStruct, Vector, VectorStruct},
+    types::{
+        Filter, Payload, PayloadSelector, PayloadSelectorExclude, PointIdType, QuantizationConfig,
+        ScalarQuantization, ScalarQuantizationConfig, ScalarType, ScoreType, SearchParams,
+        WithPayloadInterface, WithVector,
+    },
+};
+use std::collections::HashMap;
+use storage::content_manager::collection_meta_ops::CreateCollection;
+
+const DEFAULT_LIMIT: usize = 10;
+
+/// Select only the given payload keys, e.g. for [`ScrollRequestBuilder::with_payload`].
+pub fn payload_include<S: Into<String>>(keys: impl IntoIterator<Item = S>) -> WithPayloadInterface {
+    WithPayloadInterface::Fields(keys.into_iter().map(Into::into).collect())
+}
+
+/// Select the whole payload except the given keys.
+pub fn payload_exclude<S: Into<String>>(keys: impl IntoIterator<Item = S>) -> WithPayloadInterface {
+    let keys = keys.into_iter().map(Into::into).collect();
+    WithPayloadInterface::Selector(PayloadSelector::Exclude(PayloadSelectorExclude::new(keys)))
+}
+
+/// Builder for [`SearchRequest`].
+///
+/// ```ignore
+/// let req = SearchRequestBuilder::new(embedding)
+///     .limit(10)
+///     .with_payload(true)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct SearchRequestBuilder {
+    vector: NamedVectorStruct,
+    filter: Option<Filter>,
+    params: Option<SearchParams>,
+    limit: usize,
+    offset: Option<usize>,
+    with_payload: Option<WithPayloadInterface>,
+    with_vector: Option<WithVector>,
+    score_threshold: Option<ScoreType>,
+    shard_key: Option<ShardKeySelector>,
+}
+
+/// Shorter name for [`SearchRequestBuilder`].
+pub type SearchBuilder = SearchRequestBuilder;
+
+impl SearchRequestBuilder {
+    /// start a search for the given query vector, returning 10 results by default
+    pub fn new(vector: impl Into<NamedVectorStruct>) -> Self {
+        Self {
+            vector: vector.into(),
+            filter: None,
+            params: None,
+            limit: DEFAULT_LIMIT,
+            offset: None,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            shard_key: None,
+        }
+    }
+
+    /// max number of results
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// number of results to skip
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// only return points matching the filter
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// whether to return the payload of the results
+    pub fn with_payload(mut self, with_payload: bool) -> Self {
+        self.with_payload = Some(WithPayloadInterface::Bool(with_payload));
+        self
+    }
+
+    /// only return these payload keys of the results
+    pub fn include_payload<S: Into<String>>(mut self, keys: impl IntoIterator<Item = S>) -> Self {
+        self.with_payload = Some(payload_include(keys));
+        self
+    }
+
+    /// return the payload of the results without these keys
+    pub fn exclude_payload<S: Into<String>>(mut self, keys: impl IntoIterator<Item = S>) -> Self {
+        self.with_payload = Some(payload_exclude(keys));
+        self
+    }
+
+    /// whether to return the vectors of the results
+    pub fn with_vector(mut self, with_vector: bool) -> Self {
+        self.with_vector = Some(WithVector::Bool(with_vector));
+        self
+    }
+
+    /// drop results scoring worse than the threshold
+    pub fn score_threshold(mut self, score_threshold: ScoreType) -> Self {
+        self.score_threshold = Some(score_threshold);
+        self
+    }
+
+    /// additional search params, e.g. `hnsw_ef` or `exact`
+    pub fn params(mut self, params: SearchParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// only search the shards with the given key
+    pub fn shard_key(mut self, shard_key: ShardKeySelector) -> Self {
+        self.shard_key = Some(shard_key);
+        self
+    }
+
+    /// build the request
+    pub fn build(self) -> SearchRequest {
+        SearchRequest {
+            search_request: SearchRequestInternal {
+                vector: self.vector,
+                filter: self.filter,
+                params: self.params,
+                limit: self.limit,
+                offset: self.offset,
+                with_payload: self.with_payload,
+                with_vector: self.with_vector,
+                score_threshold: self.score_threshold,
+            },
+            shard_key: self.shard_key,
+        }
+    }
+}
+
+/// Builder for [`RecommendRequest`].
+///
+/// ```ignore
+/// let req = RecommendRequestBuilder::new()
+///     .positive(vec![1.into(), 2.into()])
+///     .negative(vec![3.into()])
+///     .limit(5)
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct RecommendRequestBuilder {
+    positive: Vec<RecommendExample>,
+    negative: Vec<RecommendExample>,
+    strategy: Option<RecommendStrategy>,
+    filter: Option<Filter>,
+    params: Option<SearchParams>,
+    limit: usize,
+    offset: Option<usize>,
+    with_payload: Option<WithPayloadInterface>,
+    with_vector: Option<WithVector>,
+    score_threshold: Option<ScoreType>,
+    using: Option<UsingVector>,
+    lookup_from: Option<LookupLocation>,
+    shard_key: Option<ShardKeySelector>,
+}
+
+impl Default for RecommendRequestBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecommendRequestBuilder {
+    /// start an empty recommendation, returning 10 results by default
+    pub fn new() -> Self {
+        Self {
+            positive: vec![],
+            negative: vec![],
+            strategy: None,
+            filter: None,
+            params: None,
+            limit: DEFAULT_LIMIT,
+            offset: None,
+            with_payload: None,
+            with_vector: None,
+            score_threshold: None,
+            using: None,
+            lookup_from: None,
+            shard_key: None,
+        }
+    }
+
+    /// look for points similar to these
+    pub fn positive(mut self, ids: impl IntoIterator<Item = PointIdType>) -> Self {
+        self.positive
+            .extend(ids.into_iter().map(RecommendExample::PointId));
+        self
+    }
+
+    /// look for points dissimilar to these
+    pub fn negative(mut self, ids: impl IntoIterator<Item = PointIdType>) -> Self {
+        self.negative
+            .extend(ids.into_iter().map(RecommendExample::PointId));
+        self
+    }
+
+    /// named vector to compare with
+    pub fn using(mut self, vector_name: impl Into<String>) -> Self {
+        self.using = Some(UsingVector::Name(vector_name.into()));
+        self
+    }
+
+    /// how to combine the examples
+    pub fn strategy(mut self, strategy: RecommendStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// read the example vectors from another collection
+    pub fn lookup_from(mut self, collection_name: impl Into<String>) -> Self {
+        self.lookup_from = Some(LookupLocation {
+            collection: collection_name.into(),
+            vector: None,
+            shard_key: None,
+        });
+        self
+    }
+
+    /// max number of results
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// number of results to skip
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// only return points matching the filter
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// whether to return the payload of the results
+    pub fn with_payload(mut self, with_payload: bool) -> Self {
+        self.with_payload = Some(WithPayloadInterface::Bool(with_payload));
+        self
+    }
+
+    /// only return these payload keys of the results
+    pub fn include_payload<S: Into<String>>(mut self, keys: impl IntoIterator<Item = S>) -> Self {
+        self.with_payload = Some(payload_include(keys));
+        self
+    }
+
+    /// return the payload of the results without these keys
+    pub fn exclude_payload<S: Into<String>>(mut self, keys: impl IntoIterator<Item = S>) -> Self {
+        self.with_payload = Some(payload_exclude(keys));
+        self
+    }
+
+    /// whether to return the vectors of the results
+    pub fn with_vector(mut self, with_vector: bool) -> Self {
+        self.with_vector = Some(WithVector::Bool(with_vector));
+        self
+    }
+
+    /// drop results scoring worse than the threshold
+    pub fn score_threshold(mut self, score_threshold: ScoreType) -> Self {
+        self.score_threshold = Some(score_threshold);
+        self
+    }
+
+    /// additional search params, e.g. `hnsw_ef` or `exact`
+    pub fn params(mut self, params: SearchParams) -> Self {
+        self.params = Some(params);
+        self
+    }
+
+    /// only search the shards with the given key
+    pub fn shard_key(mut self, shard_key: ShardKeySelector) -> Self {
+        self.shard_key = Some(shard_key);
+        self
+    }
+
+    /// build the request, failing if there is no example to recommend from
+    pub fn build(self) -> Result<RecommendRequest, QdrantError> {
+        if self.positive.is_empty() && self.negative.is_empty() {
+            return Err(QdrantError::BadInput(
+                "At least one positive or negative example is required".to_string(),
+            ));
+        }
+
+        Ok(RecommendRequest {
+            recommend_request: RecommendRequestInternal {
+                positive: self.positive,
+                negative: self.negative,
+                strategy: self.strategy,
+                filter: self.filter,
+                params: self.params,
+                limit: self.limit,
+                offset: self.offset,
+                with_payload: self.with_payload,
+                with_vector: self.with_vector,
+                score_threshold: self.score_threshold,
+                using: self.using,
+                lookup_from: self.lookup_from,
+            },
+            shard_key: self.shard_key,
+        })
+    }
+}
+
+/// Builder for [`ScrollRequest`].
+///
+/// Without any option it scrolls every point with its payload and without vectors.
+///
+/// There is deliberately no `order_by`: the bundled engine (qdrant 1.7) has no ordered scroll,
+/// and sorting pages client-side would break `offset` pagination. Points are always returned
+/// in id order. To walk a payload field in order, page with a `Range` condition on it and
+/// sort each page.
+#[derive(Debug, Clone)]
+pub struct ScrollRequestBuilder {
+    offset: Option<PointIdType>,
+    limit: Option<usize>,
+    filter: Option<Filter>,
+    with_payload: WithPayloadInterface,
+    with_vector: WithVector,
+    shard_key: Option<ShardKeySelector>,
+}
+
+impl Default for ScrollRequestBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ScrollRequestBuilder {
+    /// scroll everything with payload
+    pub fn new() -> Self {
+        Self {
+            offset: None,
+            limit: None,
+            filter: None,
+            with_payload: WithPayloadInterface::Bool(true),
+            with_vector: WithVector::Bool(false),
+            shard_key: None,
+        }
+    }
+
+    /// only return points matching the filter
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// max number of points per page
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// start from this point id, usually the `next_page_offset` of the previous page
+    pub fn offset(mut self, offset: PointIdType) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// which payload to return
+    pub fn with_payload(mut self, with_payload: impl Into<WithPayloadInterface>) -> Self {
+        self.with_payload = with_payload.into();
+        self
+    }
+
+    /// which vectors to return
+    pub fn with_vector(mut self, with_vector: impl Into<WithVector>) -> Self {
+        self.with_vector = with_vector.into();
+        self
+    }
+
+    /// only scroll the shards with the given key
+    pub fn shard_key(mut self, shard_key: ShardKeySelector) -> Self {
+        self.shard_key = Some(shard_key);
+        self
+    }
+
+    /// build the request
+    pub fn build(self) -> ScrollRequest {
+        ScrollRequest {
+            scroll_request: ScrollRequestInternal {
+                offset: self.offset,
+                limit: self.limit,
+                filter: self.filter,
+                with_payload: Some(self.with_payload),
+                with_vector: self.with_vector,
+            },
+            shard_key: self.shard_key,
+        }
+    }
+}
+
+/// Builder for a [`PointStruct`] with several named vectors.
+///
+/// ```ignore
+/// let point = PointBuilder::new(1)
+///     .vector("text", text_embedding)
+///     .vector("image", image_embedding)
+///     .payload(payload)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PointBuilder {
+    id: PointIdType,
+    vectors: HashMap<String, Vector>,
+    payload: Option<Payload>,
+}
+
+impl PointBuilder {
+    /// a point without vectors or payload
+    pub fn new(id: impl Into<PointIdType>) -> Self {
+        Self {
+            id: id.into(),
+            vectors: HashMap::new(),
+            payload: None,
+        }
+    }
+
+    /// add a named vector, replacing any previous vector with the same name
+    pub fn vector(mut self, name: impl Into<String>, vector: impl Into<Vector>) -> Self {
+        self.vectors.insert(name.into(), vector.into());
+        self
+    }
+
+    /// payload of the point
+    pub fn payload(mut self, payload: Payload) -> Self {
+        self.payload = Some(payload);
+        self
+    }
+
+    /// build the point
+    pub fn build(self) -> PointStruct {
+        PointStruct {
+            id: self.id,
+            vector: VectorStruct::Multi(self.vectors),
+            payload: self.payload,
+        }
+    }
+}
+
+/// Builder for [`CreateCollection`], everything not set uses the server defaults.
+///
+/// ```ignore
+/// let data = CollectionBuilder::new(vectors_config)
+///     .hnsw(32, 200)
+///     .scalar_quantization()
+///     .on_disk_payload(true)
+///     .build();
+/// client.create_collection_with("docs", data).await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct CollectionBuilder {
+    data: CreateCollection,
+}
+
+impl CollectionBuilder {
+    /// a collection with the given vectors and the engine defaults for everything else
+    pub fn new(vectors: VectorsConfig) -> Self {
+        Self {
+            data: CreateCollection {
+                vectors,
+                shard_number: None,
+                sharding_method: None,
+                replication_factor: None,
+                write_consistency_factor: None,
+                on_disk_payload: None,
+                hnsw_config: None,
+                wal_config: None,
+                optimizers_config: None,
+                init_from: None,
+                quantization_config: None,
+                sparse_vectors: None,
+            },
+        }
+    }
+
+    /// number of shards, defaults to 1
+    pub fn shard_number(mut self, shard_number: u32) -> Self {
+        self.data.shard_number = Some(shard_number);
+        self
+    }
+
+    /// use `ShardingMethod::Custom` to place points by shard key
+    pub fn sharding_method(mut self, sharding_method: ShardingMethod) -> Self {
+        self.data.sharding_method = Some(sharding_method);
+        self
+    }
+
+    /// keep payloads on disk instead of in memory
+    pub fn on_disk_payload(mut self, on_disk_payload: bool) -> Self {
+        self.data.on_disk_payload = Some(on_disk_payload);
+        self
+    }
+
+    /// set the number of edges per node and the build-time neighbours of the HNSW index
+    pub fn hnsw(mut self, m: usize, ef_construct: usize) -> Self {
+        let hnsw = self.data.hnsw_config.get_or_insert(HnswConfigDiff {
+            m: None,
+            ef_construct: None,
+            full_scan_threshold: None,
+            max_indexing_threads: None,
+            on_disk: None,
+            payload_m: None,
+        });
+        hnsw.m = Some(m);
+        hnsw.ef_construct = Some(ef_construct);
+        self
+    }
+
+    /// full HNSW index config, replacing any previous one
+    pub fn hnsw_config(mut self, hnsw_config: HnswConfigDiff) -> Self {
+        self.data.hnsw_config = Some(hnsw_config);
+        self
+    }
+
+    /// segment size and retention of the write-ahead log
+    pub fn wal_config(mut self, wal_config: WalConfigDiff) -> Self {
+        self.data.wal_config = Some(wal_config);
+        self
+    }
+
+    /// when segments get merged, vacuumed and indexed
+    pub fn optimizers_config(mut self, optimizers_config: OptimizersConfigDiff) -> Self {
+        self.data.optimizers_config = Some(optimizers_config);
+        self
+    }
+
+    /// any quantization config, replacing any previous one
+    pub fn quantization(mut self, quantization_config: QuantizationConfig) -> Self {
+        self.data.quantization_config = Some(quantization_config);
+        self
+    }
+
+    /// int8 scalar quantization, the usual choice to cut vector memory by 4x
+    pub fn scalar_quantization(self) -> Self {
+        self.quantization(QuantizationConfig::Scalar(ScalarQuantization {
+            scalar: ScalarQuantizationConfig {
+                r#type: ScalarType::Int8,
+                quantile: None,
+                always_ram: None,
+            },
+        }))
+    }
+
+    /// build the request
+    pub fn build(self) -> CreateCollection {
+        self.data
+    }
+}
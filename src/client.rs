@@ -1,37 +1,82 @@
+use crate::import::BatchController;
+use crate::instance::Worker;
+use crate::ttl::spawn_ttl_task;
 use crate::{
-    AliasRequest, AliasResponse, ColName, CollectionRequest, CollectionResponse, PointsRequest,
-    PointsResponse, QdrantClient, QdrantError, QdrantMsg, QdrantRequest, QdrantResponse,
-    QdrantResult, QueryRequest, QueryResponse,
+    AliasRequest, AliasResponse, BatchSize, BatchUpsertReport, ColName, CollectionBuilder,
+    CollectionRequest, CollectionResponse, CollectionSummary, DryRunResult, FacetHit, FacetRequest,
+    ImportReport, PayloadSchema, PointsRequest, PointsResponse, PointsUpdateOperation,
+    QdrantClient, QdrantError, QdrantErrorKind, QdrantMsg, QdrantRequest, QdrantResponse,
+    QdrantResult, QueryRequest, QueryResponse, ReadOptions, SearchMatrixOffsets, SearchMatrixPair,
+    SearchMatrixRequest, SearchMatrixResult, Settings, SnapshotRequest, SnapshotResponse,
+    StatsSnapshot, TelemetryData, TtlFormat, WriteOptions, WriteReceipt,
 };
-use collection::operations::{
-    payload_ops::{DeletePayload, SetPayload},
-    point_ops::{PointStruct, PointsSelector},
+use collection::{
+    config::ShardingMethod,
+    operations::{
+        cluster_ops::CreateShardingKey,
+        config_diff::{HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff},
+        payload_ops::{DeletePayload, SetPayload},
+        point_ops::{
+            FilterSelector, PointIdsList, PointInsertOperations, PointStruct, PointsSelector,
+        },
+        shard_key_selector::ShardKeySelector,
+        snapshot_ops::{SnapshotDescription, SnapshotPriority},
+        types::{
+            BaseGroupRequest, CollectionError, CollectionInfo, CollectionStatus, CountRequest,
+            CountRequestInternal, CountResult, DiscoverRequest, DiscoverRequestBatch,
+            OptimizersStatus, PointGroup, PointRequest, PointRequestInternal,
+            RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch,
+            RecommendRequestInternal, Record, ScrollRequest, ScrollResult, SearchGroupsRequest,
+            SearchGroupsRequestInternal, SearchRequest, SearchRequestBatch, UpdateResult,
+            VectorParams, VectorsConfig,
+        },
+        vector_ops::{DeleteVectors, PointVectors, UpdateVectors},
+        CreateFieldIndex,
+    },
+    shards::shard::ShardId,
+};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
+use segment::{
+    data_types::vectors::{
+        BatchVectorStruct, NamedVectorStruct, Vector, VectorStruct, DEFAULT_VECTOR_NAME,
+    },
     types::{
-        CollectionError, CollectionInfo, CountRequest, CountRequestInternal, PointGroup,
-        PointRequest, RecommendGroupsRequest, RecommendRequest, RecommendRequestBatch, Record,
-        SearchGroupsRequest, SearchRequest, SearchRequestBatch, UpdateResult, VectorsConfig,
+        Distance, Filter, PayloadFieldSchema, PointIdType, QuantizationConfig, ScoredPoint,
+        ShardKey, WithPayloadInterface, WithVector,
     },
-    vector_ops::{DeleteVectors, PointVectors, UpdateVectors},
 };
-use segment::types::{Filter, ScoredPoint};
-use std::{mem::ManuallyDrop, thread};
-use storage::content_manager::collection_meta_ops::{CreateCollection, UpdateCollection};
-use tokio::sync::{
-    mpsc,
-    oneshot::{self, error::TryRecvError},
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    iter, mem,
+    path::{Path, PathBuf},
+    pin::pin,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+use storage::content_manager::{
+    collection_meta_ops::{AliasOperations, CreateCollection, UpdateCollection},
+    errors::StorageError,
 };
+use tokio::{
+    fs::File,
+    io::{AsyncBufReadExt, BufReader},
+    sync::{mpsc, oneshot},
+    time,
+};
+use tokio_util::sync::CancellationToken;
 use tracing::warn;
+use validator::Validate;
+
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(1);
+/// characters Qdrant rejects in collection and alias names
+const INVALID_COLLECTION_NAME_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+const MAX_COLLECTION_NAME_LEN: usize = 255;
 
 impl Drop for QdrantClient {
     fn drop(&mut self) {
-        // drop the tx channel to terminate the qdrant thread
-        unsafe {
-            ManuallyDrop::drop(&mut self.tx);
-        }
-        while let Err(TryRecvError::Empty) = self.terminated_rx.try_recv() {
-            warn!("Waiting for qdrant to terminate");
-            thread::sleep(std::time::Duration::from_millis(100));
-        }
+        self.abort_ttl_tasks();
+        // the worker itself is shut down when it is dropped
     }
 }
 
@@ -42,52 +87,141 @@ impl QdrantClient {
         name: impl Into<String>,
         config: VectorsConfig,
     ) -> Result<bool, QdrantError> {
-        let data = CreateCollection {
-            vectors: config,
-            shard_number: None,
-            sharding_method: None,
-            replication_factor: None,
-            write_consistency_factor: None,
-            on_disk_payload: None,
-            hnsw_config: None,
-            wal_config: None,
-            optimizers_config: None,
-            init_from: None,
-            quantization_config: None,
-            sparse_vectors: None,
-        };
+        self.create_collection_with(name, CollectionBuilder::new(config).build())
+            .await
+    }
 
-        let msg = CollectionRequest::Create((name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Collection(CollectionResponse::Create(v))) => Ok(v),
+    /// Create a new collection with the full set of options, see [`CollectionBuilder`].
+    pub async fn create_collection_with(
+        &self,
+        name: impl Into<String>,
+        data: CreateCollection,
+    ) -> Result<bool, QdrantError> {
+        let name = name.into();
+        validate_collection_name(&name)?;
+        validate_input(&data)?;
+        if let Some(quantization) = &data.quantization_config {
+            validate_quantization(&data.vectors, quantization)?;
+        }
 
+        let config = data.vectors.clone();
+        let msg = CollectionRequest::Create((name.clone(), data));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::Create(v))) => {
+                self.vectors_configs.lock().unwrap().insert(name, config);
+                Ok(v)
+            }
+            Err(e) if is_already_exists(&e) => Err(QdrantError::CollectionAlreadyExists { name }),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// Create a new collection, then return its info as resolved by Qdrant, with the defaults
+    /// of the HNSW, optimizers and WAL configs filled in.
+    pub async fn create_collection_returning_info(
+        &self,
+        name: impl Into<String>,
+        config: VectorsConfig,
+    ) -> Result<CollectionInfo, QdrantError> {
+        let name = name.into();
+        self.create_collection(name.clone(), config).await?;
+        self.get_collection(name.clone())
+            .await?
+            .ok_or_else(|| collection_not_found(&name))
+    }
+
     /// List all collections.
     pub async fn list_collections(&self) -> Result<Vec<String>, QdrantError> {
-        match send_request(&self.tx, CollectionRequest::List.into()).await {
+        match send_request(&self.sender(), CollectionRequest::List.into()).await {
             Ok(QdrantResponse::Collection(CollectionResponse::List(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// List all collections with their info, in a single round trip to the worker.
+    pub async fn list_collections_detailed(
+        &self,
+    ) -> Result<Vec<(String, CollectionInfo)>, QdrantError> {
+        let msg = CollectionRequest::ListDetailed;
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::ListDetailed(v))) => {
+                let mut configs = self.vectors_configs.lock().unwrap();
+                for (name, info) in &v {
+                    configs.insert(name.clone(), info.config.params.vectors.clone());
+                }
+                Ok(v)
+            }
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Check whether the collection (or an alias of it) exists.
+    pub async fn collection_exists(&self, name: impl Into<String>) -> Result<bool, QdrantError> {
+        let msg = CollectionRequest::Exists(name.into());
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::Exists(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Check whether each of the given collections (or an alias of it) exists, in order, with a
+    /// single listing of the collections and one of the aliases.
+    pub async fn collections_exist<I, S>(&self, names: I) -> Result<Vec<bool>, QdrantError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut existing: HashSet<String> = self.list_collections().await?.into_iter().collect();
+        existing.extend(
+            self.list_aliases()
+                .await?
+                .into_iter()
+                .map(|(_, alias)| alias),
+        );
+        Ok(names
+            .into_iter()
+            .map(|name| existing.contains(name.as_ref()))
+            .collect())
+    }
+
     /// Get collection info by name.
     pub async fn get_collection(
         &self,
         name: impl Into<String>,
     ) -> Result<Option<CollectionInfo>, QdrantError> {
-        match send_request(&self.tx, CollectionRequest::Get(name.into()).into()).await {
-            Ok(QdrantResponse::Collection(CollectionResponse::Get(v))) => Ok(Some(v)),
+        let name = name.into();
+        match send_request(&self.sender(), CollectionRequest::Get(name.clone()).into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::Get(v))) => {
+                let config = v.config.params.vectors.clone();
+                self.vectors_configs.lock().unwrap().insert(name, config);
+                Ok(Some(v))
+            }
             Err(QdrantError::Collection(CollectionError::NotFound { .. })) => Ok(None),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// Vectors config of the collection as last seen by this client, through
+    /// `create_collection` or `get_collection`. Used to check vectors before sending them.
+    pub fn cached_collection_config(&self, name: &str) -> Option<VectorsConfig> {
+        self.vectors_configs.lock().unwrap().get(name).cloned()
+    }
+
+    /// Get a flat summary of the collection, suitable for listing.
+    pub async fn collection_summary(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<Option<CollectionSummary>, QdrantError> {
+        let name = name.into();
+        let info = self.get_collection(name.clone()).await?;
+        Ok(info.map(|info| CollectionSummary::new(name, &info)))
+    }
+
     /// Update collection info by name.
     pub async fn update_collection(
         &self,
@@ -95,17 +229,267 @@ impl QdrantClient {
         data: UpdateCollection,
     ) -> Result<bool, QdrantError> {
         let msg = CollectionRequest::Update((name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Collection(CollectionResponse::Update(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// Enable quantization on an existing collection.
+    ///
+    /// Stored vectors are re-encoded by the optimizer in the background, so this returns
+    /// right away. Use `wait_for_optimization` to track progress.
+    pub async fn enable_quantization(
+        &self,
+        name: impl Into<String>,
+        config: QuantizationConfig,
+    ) -> Result<bool, QdrantError> {
+        let name = name.into();
+        validate_input(&config)?;
+        let info = self
+            .get_collection(name.clone())
+            .await?
+            .ok_or_else(|| collection_not_found(&name))?;
+        validate_quantization(&info.config.params.vectors, &config)?;
+
+        let quantization_config = match config {
+            QuantizationConfig::Scalar(v) => QuantizationConfigDiff::Scalar(v),
+            QuantizationConfig::Product(v) => QuantizationConfigDiff::Product(v),
+            QuantizationConfig::Binary(v) => QuantizationConfigDiff::Binary(v),
+        };
+        let data = UpdateCollection {
+            vectors: None,
+            optimizers_config: None,
+            params: None,
+            hnsw_config: None,
+            quantization_config: Some(quantization_config),
+        };
+        self.update_collection(name, data).await
+    }
+
+    /// Change the HNSW params of an existing collection, only the fields set in `config`.
+    ///
+    /// Existing segments keep their index until the optimizer rebuilds them, so a higher
+    /// `ef_construct` or `m` only takes effect gradually. Use `wait_for_optimization` to
+    /// track progress.
+    ///
+    /// ```ignore
+    /// let config = HnswConfigDiff {
+    ///     ef_construct: Some(256),
+    ///     ..Default::default()
+    /// };
+    /// client.update_hnsw_config("docs", config).await?;
+    /// ```
+    pub async fn update_hnsw_config(
+        &self,
+        name: impl Into<String>,
+        config: HnswConfigDiff,
+    ) -> Result<bool, QdrantError> {
+        let data = UpdateCollection {
+            vectors: None,
+            optimizers_config: None,
+            params: None,
+            hnsw_config: Some(config),
+            quantization_config: None,
+        };
+        self.update_collection(name, data).await
+    }
+
+    /// Change the optimizer thresholds of the collection at runtime.
+    ///
+    /// To get the HNSW index built right after a bulk load instead of waiting for the
+    /// optimizer heuristics, lower `indexing_threshold` (in KB) so that every segment
+    /// qualifies, then `wait_for_optimization`. Note that `0` disables indexing altogether.
+    ///
+    /// ```ignore
+    /// let config = OptimizersConfigDiff {
+    ///     indexing_threshold: Some(1),
+    ///     ..Default::default()
+    /// };
+    /// client.update_optimizers("docs", config).await?;
+    /// client.wait_for_optimization("docs", Duration::from_secs(1)).await?;
+    /// ```
+    pub async fn update_optimizers(
+        &self,
+        name: impl Into<String>,
+        config: OptimizersConfigDiff,
+    ) -> Result<bool, QdrantError> {
+        let msg = CollectionRequest::UpdateOptimizers((name.into(), config));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::UpdateOptimizers(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Status of the optimizers of the collection, `None` if it doesn't exist. An `Error`
+    /// means optimizations stopped until the cause is fixed.
+    pub async fn optimizer_status(
+        &self,
+        name: impl Into<String>,
+    ) -> Result<Option<OptimizersStatus>, QdrantError> {
+        let info = self.get_collection(name).await?;
+        Ok(info.map(|info| info.optimizer_status))
+    }
+
+    /// Wait until the collection has no pending optimizations.
+    ///
+    /// Polls the collection status every `poll_interval`. Wrap the call in
+    /// `tokio::time::timeout` to bound the wait. Fails as soon as the optimizers report an
+    /// error, since they stop until the cause is fixed.
+    pub async fn wait_for_optimization(
+        &self,
+        name: impl Into<String>,
+        poll_interval: Duration,
+    ) -> Result<CollectionInfo, QdrantError> {
+        let name = name.into();
+        loop {
+            match self.get_collection(name.clone()).await? {
+                Some(info) if matches!(info.status, CollectionStatus::Green) => return Ok(info),
+                Some(info) if matches!(info.status, CollectionStatus::Red) => {
+                    let reason = match info.optimizer_status {
+                        OptimizersStatus::Error(e) => e,
+                        OptimizersStatus::Ok => "unknown error".to_string(),
+                    };
+                    return Err(QdrantError::Collection(CollectionError::service_error(
+                        format!("Optimizers of collection `{name}` failed: {reason}"),
+                    )));
+                }
+                Some(_) => time::sleep(poll_interval).await,
+                None => return Err(collection_not_found(&name)),
+            }
+        }
+    }
+
     /// Delete collection by name.
+    ///
+    /// The collection is gone once this returns, but its files are removed in the background.
+    /// Use [`QdrantClient::delete_collection_with_options`] with `wait` to block until they are.
     pub async fn delete_collection(&self, name: impl Into<String>) -> Result<bool, QdrantError> {
-        match send_request(&self.tx, CollectionRequest::Delete(name.into()).into()).await {
-            Ok(QdrantResponse::Collection(CollectionResponse::Delete(v))) => Ok(v),
+        self.delete_collection_with_options(name, WriteOptions::default())
+            .await
+    }
+
+    /// Delete collection by name. With `wait`, only return once its files are removed from disk.
+    ///
+    /// The `ordering` and `shard_key` options don't apply, as deleting a collection isn't routed
+    /// through the shard replicas.
+    pub async fn delete_collection_with_options(
+        &self,
+        name: impl Into<String>,
+        options: WriteOptions,
+    ) -> Result<bool, QdrantError> {
+        let name = name.into();
+        let msg = CollectionRequest::DeleteWithOptions((name.clone(), options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::Delete(v))) => {
+                self.vectors_configs.lock().unwrap().remove(&name);
+                self.payload_schemas.lock().unwrap().remove(&name);
+                Ok(v)
+            }
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Delete the collections concurrently, returning whether each one was deleted. Missing
+    /// collections are reported as not deleted, any other error fails the whole call.
+    pub async fn delete_collections(
+        &self,
+        names: Vec<String>,
+    ) -> Result<Vec<(String, bool)>, QdrantError> {
+        let deletes = names.into_iter().map(|name| async move {
+            match self.delete_collection(name.clone()).await {
+                Ok(deleted) => Ok((name, deleted)),
+                Err(e) if e.kind() == QdrantErrorKind::NotFound => Ok((name, false)),
+                Err(e) => Err(e),
+            }
+        });
+        future::try_join_all(deletes).await
+    }
+
+    /// Delete the collection if it exists, then create it from scratch. The old files are
+    /// removed from disk before the new collection is created.
+    pub async fn recreate_collection(
+        &self,
+        name: impl Into<String>,
+        config: VectorsConfig,
+    ) -> Result<bool, QdrantError> {
+        let name = name.into();
+        let options = WriteOptions {
+            wait: true,
+            ..Default::default()
+        };
+        match self
+            .delete_collection_with_options(name.clone(), options)
+            .await
+        {
+            Ok(_) => {}
+            Err(e) if e.kind() == QdrantErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+        self.create_collection(name, config).await
+    }
+
+    /// Create a payload index on the field, and wait until it is built.
+    pub async fn create_field_index(
+        &self,
+        name: impl Into<String>,
+        field_name: impl Into<String>,
+        field_schema: PayloadFieldSchema,
+    ) -> Result<UpdateResult, QdrantError> {
+        let data = CreateFieldIndex {
+            field_name: field_name.into(),
+            field_schema: Some(field_schema),
+        };
+        let msg = CollectionRequest::CreateFieldIndex((name.into(), data));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::CreateFieldIndex(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Delete the payload index of the field.
+    pub async fn delete_field_index(
+        &self,
+        name: impl Into<String>,
+        field_name: impl Into<String>,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg = CollectionRequest::DeleteFieldIndex((name.into(), field_name.into()));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::DeleteFieldIndex(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Create the shards of a new shard key, so points can be routed to it.
+    ///
+    /// The collection needs to be created with the `Custom` sharding method.
+    pub async fn create_shard_key(
+        &self,
+        name: impl Into<String>,
+        data: CreateShardingKey,
+    ) -> Result<bool, QdrantError> {
+        let msg = CollectionRequest::CreateShardKey((name.into(), data));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::CreateShardKey(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Drop the shards of a shard key, deleting all points routed to it.
+    pub async fn drop_shard_key(
+        &self,
+        name: impl Into<String>,
+        shard_key: ShardKey,
+    ) -> Result<bool, QdrantError> {
+        let msg = CollectionRequest::DropShardKey((name.into(), shard_key));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Collection(CollectionResponse::DropShardKey(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
@@ -117,17 +501,67 @@ impl QdrantClient {
         collection_name: impl Into<String>,
         alias_name: impl Into<String>,
     ) -> Result<bool, QdrantError> {
-        let msg = AliasRequest::Create((collection_name.into(), alias_name.into()));
-        match send_request(&self.tx, msg.into()).await {
+        let (collection_name, alias_name) = (collection_name.into(), alias_name.into());
+        validate_collection_name(&collection_name)?;
+        validate_collection_name(&alias_name)?;
+        let msg = AliasRequest::Create((collection_name, alias_name));
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Alias(AliasResponse::Create(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// Create a collection whose upserts are checked against the given payload schema.
+    ///
+    /// The schema is kept by this client only, see [`QdrantClient::set_payload_schema`].
+    pub async fn create_collection_with_schema(
+        &self,
+        name: impl Into<String>,
+        config: VectorsConfig,
+        schema: PayloadSchema,
+    ) -> Result<bool, QdrantError> {
+        let name = name.into();
+        let created = self.create_collection(name.clone(), config).await?;
+        self.set_payload_schema(name, Some(schema));
+        Ok(created)
+    }
+
+    /// Set or clear the payload schema enforced on upserts to the collection.
+    ///
+    /// Schemas are not persisted, so they need to be set again after the client starts.
+    pub fn set_payload_schema(&self, name: impl Into<String>, schema: Option<PayloadSchema>) {
+        let mut schemas = self.payload_schemas.lock().unwrap();
+        match schema {
+            Some(schema) => schemas.insert(name.into(), schema),
+            None => schemas.remove(&name.into()),
+        };
+    }
+
+    /// Create a collection and attach an alias to it.
+    ///
+    /// If the alias can't be created, the new collection is deleted again and the alias
+    /// error is returned, so the collection never exists without its alias.
+    pub async fn create_collection_with_alias(
+        &self,
+        name: impl Into<String>,
+        config: VectorsConfig,
+        alias_name: impl Into<String>,
+    ) -> Result<bool, QdrantError> {
+        let name = name.into();
+        let created = self.create_collection(name.clone(), config).await?;
+        if let Err(e) = self.create_alias(name.clone(), alias_name).await {
+            if let Err(rollback_err) = self.delete_collection(name.clone()).await {
+                warn!("Failed to roll back collection {name}: {rollback_err}");
+            }
+            return Err(e);
+        }
+        Ok(created)
+    }
+
     /// List all aliases.
     pub async fn list_aliases(&self) -> Result<Vec<(ColName, String)>, QdrantError> {
-        match send_request(&self.tx, AliasRequest::List.into()).await {
+        match send_request(&self.sender(), AliasRequest::List.into()).await {
             Ok(QdrantResponse::Alias(AliasResponse::List(v))) => {
                 let res = v
                     .aliases
@@ -141,12 +575,48 @@ impl QdrantClient {
         }
     }
 
+    /// Apply several alias changes at once: either all of them are applied, or none.
+    ///
+    /// Swap an alias to a new collection without a window where it doesn't exist:
+    ///
+    /// ```ignore
+    /// let actions = vec![
+    ///     AliasOperations::from(DeleteAlias { alias_name: "docs".into() }),
+    ///     AliasOperations::from(CreateAlias {
+    ///         collection_name: "docs_v2".into(),
+    ///         alias_name: "docs".into(),
+    ///     }),
+    /// ];
+    /// client.change_aliases(actions).await?;
+    /// ```
+    pub async fn change_aliases(&self, actions: Vec<AliasOperations>) -> Result<bool, QdrantError> {
+        validate_alias_operations(&actions)?;
+        let msg = AliasRequest::Change(actions);
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Alias(AliasResponse::Change(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Resolve a name that may be an alias to the name of the collection, which is returned
+    /// as is if it's already a collection. Fails with a `NotFound` error if it's neither.
+    pub async fn resolve_alias(&self, name: impl Into<String>) -> Result<String, QdrantError> {
+        let msg = AliasRequest::Resolve(name.into());
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Alias(AliasResponse::Resolve(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
     /// Get aliases for collection.
     pub async fn get_aliases(
         &self,
         collection_name: impl Into<String>,
     ) -> Result<Vec<(ColName, String)>, QdrantError> {
-        match send_request(&self.tx, AliasRequest::Get(collection_name.into()).into()).await {
+        let msg = AliasRequest::Get(collection_name.into());
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Alias(AliasResponse::Get(v))) => {
                 let res = v
                     .aliases
@@ -163,7 +633,7 @@ impl QdrantClient {
     /// Delete alias.
     pub async fn delete_alias(&self, alias_name: impl Into<String>) -> Result<bool, QdrantError> {
         let msg = AliasRequest::Delete(alias_name.into());
-        match send_request(&self.tx, msg.into()).await {
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Alias(AliasResponse::Delete(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
@@ -176,175 +646,893 @@ impl QdrantClient {
         old_alias_name: impl Into<String>,
         new_alias_name: impl Into<String>,
     ) -> Result<bool, QdrantError> {
-        let msg = AliasRequest::Rename((old_alias_name.into(), new_alias_name.into()));
-        match send_request(&self.tx, msg.into()).await {
+        let new_alias_name = new_alias_name.into();
+        validate_collection_name(&new_alias_name)?;
+        let msg = AliasRequest::Rename((old_alias_name.into(), new_alias_name));
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Alias(AliasResponse::Rename(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// get points from collection
-    pub async fn get_points(
+    /// Create a snapshot of the collection, stored in the snapshots directory.
+    pub async fn create_snapshot(
         &self,
-        collection_name: impl Into<String>,
-        data: PointRequest,
-    ) -> Result<Vec<Record>, QdrantError> {
-        let msg = PointsRequest::Get((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::Get(v))) => Ok(v),
+        name: impl Into<String>,
+    ) -> Result<SnapshotDescription, QdrantError> {
+        let msg = SnapshotRequest::Create(name.into());
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Snapshot(SnapshotResponse::Create(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// upsert points to collection
-    pub async fn upsert_points(
+    /// List snapshots of the collection.
+    pub async fn list_snapshots(
         &self,
-        collection_name: impl Into<String>,
-        points: Vec<PointStruct>,
-    ) -> Result<UpdateResult, QdrantError> {
-        let msg = PointsRequest::Upsert((collection_name.into(), points.into()));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::Upsert(v))) => Ok(v),
+        name: impl Into<String>,
+    ) -> Result<Vec<SnapshotDescription>, QdrantError> {
+        let msg = SnapshotRequest::List(name.into());
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Snapshot(SnapshotResponse::List(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// delete points from collection
-    pub async fn delete_points(
-        &self,
-        collection_name: impl Into<String>,
-        points: PointsSelector,
-    ) -> Result<UpdateResult, QdrantError> {
-        let msg = PointsRequest::Delete((collection_name.into(), points));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::Delete(v))) => Ok(v),
+    /// Snapshot all collections and aliases into a single archive, stored in the snapshots
+    /// directory. Meant for backups of the whole storage.
+    pub async fn create_full_snapshot(&self) -> Result<SnapshotDescription, QdrantError> {
+        match send_request(&self.sender(), SnapshotRequest::CreateFull.into()).await {
+            Ok(QdrantResponse::Snapshot(SnapshotResponse::CreateFull(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// count points in collection
-    pub async fn count_points(
-        &self,
-        collection_name: impl Into<String>,
-        filter: Option<Filter>,
-        exact: bool,
-    ) -> Result<usize, QdrantError> {
-        let data = CountRequest {
-            count_request: CountRequestInternal { filter, exact },
-            shard_key: None,
-        };
-        let msg = PointsRequest::Count((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::Count(v))) => Ok(v.count),
+    /// List the full snapshots of the storage.
+    pub async fn list_full_snapshots(&self) -> Result<Vec<SnapshotDescription>, QdrantError> {
+        match send_request(&self.sender(), SnapshotRequest::ListFull.into()).await {
+            Ok(QdrantResponse::Snapshot(SnapshotResponse::ListFull(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// update point vectors
-    pub async fn update_vectors(
+    /// Delete a snapshot of the collection.
+    pub async fn delete_snapshot(
         &self,
-        collection_name: impl Into<String>,
-        points: Vec<PointVectors>,
-    ) -> Result<UpdateResult, QdrantError> {
-        let data = UpdateVectors {
-            points,
-            shard_key: None,
-        };
-        let msg = PointsRequest::UpdateVectors((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::UpdateVectors(v))) => Ok(v),
+        name: impl Into<String>,
+        snapshot_name: impl Into<String>,
+    ) -> Result<bool, QdrantError> {
+        let msg = SnapshotRequest::Delete((name.into(), snapshot_name.into()));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Snapshot(SnapshotResponse::Delete(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// delete point vectors
-    pub async fn delete_vectors(
+    /// Recover the collection from a snapshot, creating it if it doesn't exist.
+    ///
+    /// `path` is a snapshot file on the local filesystem, not a URL: nothing is downloaded in
+    /// embedded mode.
+    pub async fn recover_snapshot(
         &self,
-        collection_name: impl Into<String>,
-        data: DeleteVectors,
-    ) -> Result<UpdateResult, QdrantError> {
-        let msg = PointsRequest::DeleteVectors((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::DeleteVectors(v))) => Ok(v),
+        name: impl Into<String>,
+        path: impl Into<PathBuf>,
+        priority: SnapshotPriority,
+    ) -> Result<bool, QdrantError> {
+        let msg = SnapshotRequest::Recover((name.into(), path.into(), priority));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Snapshot(SnapshotResponse::Recover(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// set point payload
-    pub async fn set_payload(
+    /// get points from collection
+    pub async fn get_points(
         &self,
         collection_name: impl Into<String>,
-        data: SetPayload,
-    ) -> Result<UpdateResult, QdrantError> {
-        let msg = PointsRequest::SetPayload((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::SetPayload(v))) => Ok(v),
-            Err(e) => Err(e),
-            res => panic!("Unexpected response: {:?}", res),
-        }
+        data: PointRequest,
+    ) -> Result<Vec<Record>, QdrantError> {
+        self.get_points_with_options(collection_name, data, ReadOptions::default())
+            .await
     }
 
-    /// delete point payload
-    pub async fn delete_payload(
+    /// get points with the given [`ReadOptions`], e.g. a `Majority` consistency to read back
+    /// a replicated write
+    pub async fn get_points_with_options(
         &self,
         collection_name: impl Into<String>,
-        data: DeletePayload,
-    ) -> Result<UpdateResult, QdrantError> {
-        let msg = PointsRequest::DeletePayload((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::DeletePayload(v))) => Ok(v),
+        data: PointRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<Record>, QdrantError> {
+        let cancel = options.cancel.clone();
+        let msg = PointsRequest::GetWithOptions((collection_name.into(), data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
+            Ok(QdrantResponse::Points(PointsResponse::Get(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// clear point payload
-    pub async fn clear_payload(
+    /// Get points along with the shard each one was read from, to debug diverging replicas
+    /// in a multi-shard collection. Every shard is asked in turn, so a shard key isn't
+    /// supported.
+    pub async fn get_points_with_shard(
         &self,
         collection_name: impl Into<String>,
-        points: PointsSelector,
-    ) -> Result<UpdateResult, QdrantError> {
-        let msg = PointsRequest::ClearPayload((collection_name.into(), points));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Points(PointsResponse::ClearPayload(v))) => Ok(v),
+        data: PointRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<(Record, ShardId)>, QdrantError> {
+        let cancel = options.cancel.clone();
+        let msg = PointsRequest::GetWithShard((collection_name.into(), data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
+            Ok(QdrantResponse::Points(PointsResponse::GetWithShard(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
-    /// search for vectors
-    pub async fn search_points(
+    /// get points by id, only returning the requested parts of their payload and vectors
+    pub async fn get_points_selective(
         &self,
         collection_name: impl Into<String>,
-        data: SearchRequest,
-    ) -> Result<Vec<ScoredPoint>, QdrantError> {
-        let msg = QueryRequest::Search((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Query(QueryResponse::Search(v))) => Ok(v),
-            Err(e) => Err(e),
-            res => panic!("Unexpected response: {:?}", res),
-        }
+        ids: Vec<PointIdType>,
+        with_payload: WithPayloadInterface,
+        with_vector: WithVector,
+    ) -> Result<Vec<Record>, QdrantError> {
+        let data = PointRequest {
+            point_request: PointRequestInternal {
+                ids,
+                with_payload: Some(with_payload),
+                with_vector,
+            },
+            shard_key: None,
+        };
+        self.get_points(collection_name, data).await
     }
 
-    // search for vectors in batch
-    pub async fn search_points_batch(
+    /// upsert points to collection
+    pub async fn upsert_points(
         &self,
         collection_name: impl Into<String>,
-        data: Vec<SearchRequest>,
-    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
-        let data = SearchRequestBatch { searches: data };
-        let msg = QueryRequest::SearchBatch((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
-            Ok(QdrantResponse::Query(QueryResponse::SearchBatch(v))) => Ok(v),
-            Err(e) => Err(e),
-            res => panic!("Unexpected response: {:?}", res),
+        points: Vec<PointStruct>,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.upsert_points_with_options(collection_name, points, WriteOptions::default())
+            .await
+    }
+
+    /// upsert points, checked like in `upsert_points`, with explicit write options
+    pub async fn upsert_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        points: Vec<PointStruct>,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let collection_name = collection_name.into();
+        self.validate_points(&collection_name, &points)?;
+        let msg = PointsRequest::UpsertWithOptions((collection_name, points.into(), options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::Upsert(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// apply several updates to the collection in order, e.g. an upsert followed by payload
+    /// changes, returning the status of each
+    ///
+    /// Stops at the first failing update; the ones before it stay applied.
+    pub async fn batch_update(
+        &self,
+        collection_name: impl Into<String>,
+        operations: Vec<PointsUpdateOperation>,
+    ) -> Result<Vec<UpdateResult>, QdrantError> {
+        self.batch_update_with_options(collection_name, operations, WriteOptions::default())
+            .await
+    }
+
+    /// batch update with the given [`WriteOptions`]
+    pub async fn batch_update_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        operations: Vec<PointsUpdateOperation>,
+        options: WriteOptions,
+    ) -> Result<Vec<UpdateResult>, QdrantError> {
+        let collection_name = collection_name.into();
+        self.validate_update_operations(&collection_name, &operations)?;
+        let msg = PointsRequest::UpdateBatch((collection_name, operations, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::UpdateBatch(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// upsert points in chunks of `chunk_size`, with up to `max_in_flight` chunks sent at once
+    ///
+    /// Stops at the first failing chunk. The report tells how many points were written in the
+    /// chunks before it, so a large ingest can resume from there. With more than one chunk in
+    /// flight, the chunks sent after the failing one may have been written too.
+    pub async fn upsert_points_chunked(
+        &self,
+        collection_name: impl Into<String>,
+        points: Vec<PointStruct>,
+        chunk_size: usize,
+        max_in_flight: usize,
+    ) -> BatchUpsertReport {
+        let collection_name = collection_name.into();
+        let mut points = points.into_iter();
+        let chunks = iter::from_fn(|| {
+            let chunk: Vec<_> = points.by_ref().take(chunk_size.max(1)).collect();
+            (!chunk.is_empty()).then_some(chunk)
+        });
+        let mut results = pin!(stream::iter(chunks)
+            .map(|chunk| {
+                let collection_name = collection_name.clone();
+                async move {
+                    let len = chunk.len();
+                    self.upsert_points(collection_name, chunk)
+                        .await
+                        .map(|_| len)
+                }
+            })
+            .buffered(max_in_flight.max(1))
+            .enumerate());
+
+        let mut report = BatchUpsertReport::default();
+        while let Some((index, res)) = results.next().await {
+            match res {
+                Ok(len) => report.succeeded += len,
+                Err(e) => {
+                    report.failed_chunk_index = Some(index);
+                    report.error = Some(e);
+                    break;
+                }
+            }
+        }
+        report
+    }
+
+    /// import points, upserting them in batches of the given size
+    pub async fn import_points<I>(
+        &self,
+        collection_name: impl Into<String>,
+        points: I,
+        batch_size: BatchSize,
+    ) -> Result<ImportReport, QdrantError>
+    where
+        I: IntoIterator<Item = PointStruct>,
+        I::IntoIter: Send,
+    {
+        let points = Box::pin(stream::iter(points.into_iter().map(Ok)));
+        self.import_batches(collection_name.into(), points, batch_size)
+            .await
+    }
+
+    /// import points from a file with one JSON encoded `PointStruct` per line
+    pub async fn import_jsonl(
+        &self,
+        collection_name: impl Into<String>,
+        path: impl AsRef<Path>,
+        batch_size: BatchSize,
+    ) -> Result<ImportReport, QdrantError> {
+        let lines = BufReader::new(File::open(path).await?).lines();
+        let points = stream::try_unfold((lines, 0), |(mut lines, mut n)| async move {
+            while let Some(line) = lines.next_line().await? {
+                n += 1;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let point = serde_json::from_str::<PointStruct>(&line)
+                    .map_err(|e| QdrantError::BadInput(format!("line {n}: {e}")))?;
+                return Ok(Some((point, (lines, n))));
+            }
+            Ok(None)
+        });
+        self.import_batches(collection_name.into(), Box::pin(points), batch_size)
+            .await
+    }
+
+    async fn import_batches(
+        &self,
+        collection_name: String,
+        mut points: impl Stream<Item = Result<PointStruct, QdrantError>> + Send + Unpin,
+        batch_size: BatchSize,
+    ) -> Result<ImportReport, QdrantError> {
+        let mut controller = BatchController::new(batch_size);
+        let mut report = ImportReport::default();
+        loop {
+            let batch: Vec<_> = points
+                .by_ref()
+                .take(controller.size())
+                .try_collect()
+                .await?;
+            if batch.is_empty() {
+                break;
+            }
+
+            let len = batch.len();
+            let start = Instant::now();
+            self.upsert_points(collection_name.clone(), batch).await?;
+            controller.observe(start.elapsed());
+
+            report.imported += len;
+            report.batches += 1;
+        }
+        report.final_batch_size = controller.size();
+        Ok(report)
+    }
+
+    /// reject upserts with a serialized payload larger than `limit` bytes, `None` for unlimited
+    pub fn set_max_payload_bytes(&self, limit: Option<usize>) {
+        self.max_payload_bytes
+            .store(limit.unwrap_or_default(), Ordering::Relaxed);
+    }
+
+    /// upsert points to collection and return a receipt that later reads can wait on
+    pub async fn upsert_points_with_receipt(
+        &self,
+        collection_name: impl Into<String>,
+        points: Vec<PointStruct>,
+    ) -> Result<WriteReceipt, QdrantError> {
+        let collection_name = collection_name.into();
+        let res = self.upsert_points(collection_name.clone(), points).await?;
+        Ok(self.receipt(collection_name, &res))
+    }
+
+    /// issue a receipt for a write that has been acknowledged
+    pub fn receipt(
+        &self,
+        collection_name: impl Into<String>,
+        result: &UpdateResult,
+    ) -> WriteReceipt {
+        self.sessions
+            .issue(collection_name.into(), result.operation_id)
+    }
+
+    /// wait until the write behind the receipt is visible to reads
+    ///
+    /// Writes sent without waiting are acknowledged before they are applied. This pushes a
+    /// no-op update through the collection's update queue and waits for it, unless an earlier
+    /// sync already covered the receipt, so reads without a receipt are never slowed down.
+    pub async fn wait_for_receipt(&self, receipt: &WriteReceipt) -> Result<(), QdrantError> {
+        if self.sessions.is_synced(receipt) {
+            return Ok(());
+        }
+
+        let clock = self.sessions.now();
+        let msg = PointsRequest::Sync(receipt.collection_name.clone());
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::Sync(_))) => {
+                self.sessions.mark_synced(&receipt.collection_name, clock);
+                Ok(())
+            }
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// wait until the operation with the given id, from an [`UpdateResult`] of a write sent
+    /// without waiting, is applied to the collection
+    ///
+    /// Updates are applied in order, so this pushes a no-op update through the collection's
+    /// update queue and waits for it. Its operation id also tells whether the given one was
+    /// issued at all. The no-op takes an operation id of its own, so every call is a write.
+    ///
+    /// Operation ids are counted per shard, so this only works for collections with a single
+    /// shard, and fails with `BadInput` for the others.
+    pub async fn wait_for_operation(
+        &self,
+        collection_name: impl Into<String>,
+        operation_id: u64,
+    ) -> Result<(), QdrantError> {
+        let collection_name = collection_name.into();
+        let info = self
+            .get_collection(collection_name.clone())
+            .await?
+            .ok_or_else(|| collection_not_found(&collection_name))?;
+        let params = &info.config.params;
+        let single_shard = params.shard_number.get() == 1
+            && !matches!(params.sharding_method, Some(ShardingMethod::Custom));
+        if !single_shard {
+            return Err(QdrantError::BadInput(format!(
+                "Collection {collection_name} has several shards, whose operation ids are unrelated"
+            )));
+        }
+
+        let msg = PointsRequest::Sync(collection_name.clone());
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::Sync(v))) => match v.operation_id {
+                Some(id) if id <= operation_id => Err(QdrantError::BadInput(format!(
+                    "Operation {operation_id} was not issued for collection {collection_name}"
+                ))),
+                _ => Ok(()),
+            },
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// delete points from collection
+    pub async fn delete_points(
+        &self,
+        collection_name: impl Into<String>,
+        points: PointsSelector,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.delete_points_with_options(collection_name, points, WriteOptions::default())
+            .await
+    }
+
+    /// delete the selected points, optionally waiting until they're gone
+    pub async fn delete_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        points: PointsSelector,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg = PointsRequest::DeleteWithOptions((collection_name.into(), points, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::Delete(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// delete the points with the given ids
+    pub async fn delete_points_by_ids(
+        &self,
+        collection_name: impl Into<String>,
+        ids: Vec<PointIdType>,
+    ) -> Result<UpdateResult, QdrantError> {
+        let selector = PointsSelector::PointIdsSelector(PointIdsList {
+            points: ids,
+            shard_key: None,
+        });
+        self.delete_points(collection_name, selector).await
+    }
+
+    /// delete all points matching the filter
+    ///
+    /// `affected` is counted right before deleting. With `dry_run` only the count is done.
+    pub async fn delete_points_by_filter(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Filter,
+        dry_run: bool,
+    ) -> Result<DryRunResult, QdrantError> {
+        let collection_name = collection_name.into();
+        let affected = self
+            .count_points(collection_name.clone(), Some(filter.clone()), true)
+            .await?;
+        if dry_run {
+            return Ok(DryRunResult {
+                affected,
+                update: None,
+            });
+        }
+
+        let selector = PointsSelector::FilterSelector(FilterSelector {
+            filter,
+            shard_key: None,
+        });
+        let update = self.delete_points(collection_name, selector).await?;
+        Ok(DryRunResult {
+            affected,
+            update: Some(update),
+        })
+    }
+
+    /// delete all points in collection, keeping its config and indexes
+    pub async fn truncate_collection(
+        &self,
+        collection_name: impl Into<String>,
+        dry_run: bool,
+    ) -> Result<DryRunResult, QdrantError> {
+        self.delete_points_by_filter(collection_name, Filter::default(), dry_run)
+            .await
+    }
+
+    /// estimate the number of points matching the filter, cheap enough for dashboards
+    ///
+    /// Uses the segment statistics and the cardinality estimation of the payload indexes, so
+    /// no points are scanned. Without a filter it's the exact number of stored points, with
+    /// one it may be off, especially for fields that aren't indexed.
+    pub async fn approximate_count(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+    ) -> Result<usize, QdrantError> {
+        self.count_points(collection_name, filter, false).await
+    }
+
+    /// count the points matching the filter exactly
+    ///
+    /// Every candidate point of every segment is checked against the filter, so this gets
+    /// slow on large collections. Prefer `approximate_count` when an estimate will do.
+    pub async fn exact_count(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+    ) -> Result<usize, QdrantError> {
+        self.count_points(collection_name, filter, true).await
+    }
+
+    /// count points in collection, see `approximate_count` and `exact_count` for the cost of
+    /// `exact`
+    pub async fn count_points(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+        exact: bool,
+    ) -> Result<usize, QdrantError> {
+        self.count_points_with_options(collection_name, filter, exact, ReadOptions::default())
+            .await
+    }
+
+    /// count points in collection with the given [`ReadOptions`]
+    pub async fn count_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+        exact: bool,
+        options: ReadOptions,
+    ) -> Result<usize, QdrantError> {
+        let res = self
+            .count_points_detailed_with_options(collection_name, filter, exact, options)
+            .await?;
+        Ok(res.count)
+    }
+
+    /// count points in collection, returning the full [`CountResult`]
+    pub async fn count_points_detailed(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+        exact: bool,
+    ) -> Result<CountResult, QdrantError> {
+        let options = ReadOptions::default();
+        self.count_points_detailed_with_options(collection_name, filter, exact, options)
+            .await
+    }
+
+    /// count points in collection with the given [`ReadOptions`], returning the full
+    /// [`CountResult`]
+    pub async fn count_points_detailed_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+        exact: bool,
+        options: ReadOptions,
+    ) -> Result<CountResult, QdrantError> {
+        let data = CountRequest {
+            count_request: CountRequestInternal { filter, exact },
+            shard_key: None,
+        };
+        let cancel = options.cancel.clone();
+        let msg = PointsRequest::CountWithOptions((collection_name.into(), data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
+            Ok(QdrantResponse::Points(PointsResponse::Count(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// scroll points, returning a page and the offset to continue from
+    pub async fn scroll_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: ScrollRequest,
+    ) -> Result<ScrollResult, QdrantError> {
+        self.scroll_points_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// scroll points with the given [`ReadOptions`]; the timeout bounds the whole page
+    pub async fn scroll_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: ScrollRequest,
+        options: ReadOptions,
+    ) -> Result<ScrollResult, QdrantError> {
+        let msg = PointsRequest::ScrollWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::Scroll(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// get a uniform random sample of n points, with payload
+    ///
+    /// The whole collection is scanned to pick the sample, so this is meant for inspection
+    /// and fixtures rather than hot paths.
+    pub async fn sample_points(
+        &self,
+        collection_name: impl Into<String>,
+        n: usize,
+    ) -> Result<Vec<Record>, QdrantError> {
+        let msg = PointsRequest::Sample((collection_name.into(), n));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::Sample(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// periodically delete points whose `ttl_field` is a unix timestamp (in seconds) in the past
+    ///
+    /// The check runs as a background task on the current Tokio runtime, so this must be called
+    /// from within one. Enabling TTL again for the same collection replaces the previous task.
+    /// The task stops when the client is dropped. Index `ttl_field` as a numeric payload field
+    /// to keep the periodic range filter cheap. A zero `check_interval` is rejected.
+    pub fn enable_ttl(
+        &self,
+        collection_name: impl Into<String>,
+        ttl_field: impl Into<String>,
+        check_interval: Duration,
+    ) -> Result<(), QdrantError> {
+        self.enable_ttl_with_format(
+            collection_name,
+            ttl_field,
+            TtlFormat::UnixSeconds,
+            check_interval,
+        )
+    }
+
+    /// like [`QdrantClient::enable_ttl`], with `ttl_field` stored in the given format, e.g.
+    /// RFC 3339 datetimes
+    pub fn enable_ttl_with_format(
+        &self,
+        collection_name: impl Into<String>,
+        ttl_field: impl Into<String>,
+        format: TtlFormat,
+        check_interval: Duration,
+    ) -> Result<(), QdrantError> {
+        if check_interval.is_zero() {
+            return Err(QdrantError::BadInput(
+                "TTL check interval must be positive".to_string(),
+            ));
+        }
+        let collection_name = collection_name.into();
+        let task = spawn_ttl_task(
+            self.sender().downgrade(),
+            collection_name.clone(),
+            ttl_field.into(),
+            format,
+            check_interval,
+        );
+        if let Some(old) = self.ttl_tasks.lock().unwrap().insert(collection_name, task) {
+            old.abort();
+        }
+        Ok(())
+    }
+
+    /// stop the TTL task of the collection, if any
+    pub fn disable_ttl(&self, collection_name: &str) {
+        if let Some(task) = self.ttl_tasks.lock().unwrap().remove(collection_name) {
+            task.abort();
+        }
+    }
+
+    /// update point vectors
+    pub async fn update_vectors(
+        &self,
+        collection_name: impl Into<String>,
+        points: Vec<PointVectors>,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.update_vectors_with_options(collection_name, points, WriteOptions::default())
+            .await
+    }
+
+    /// replace the given named vectors of existing points, keeping their other vectors
+    pub async fn update_vectors_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        points: Vec<PointVectors>,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let data = UpdateVectors {
+            points,
+            shard_key: None,
+        };
+        let msg = PointsRequest::UpdateVectorsWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::UpdateVectors(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// delete point vectors
+    pub async fn delete_vectors(
+        &self,
+        collection_name: impl Into<String>,
+        data: DeleteVectors,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.delete_vectors_with_options(collection_name, data, WriteOptions::default())
+            .await
+    }
+
+    /// remove the named vectors from the selected points
+    pub async fn delete_vectors_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: DeleteVectors,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg = PointsRequest::DeleteVectorsWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::DeleteVectors(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// set point payload
+    pub async fn set_payload(
+        &self,
+        collection_name: impl Into<String>,
+        data: SetPayload,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.set_payload_with_options(collection_name, data, WriteOptions::default())
+            .await
+    }
+
+    /// merge the payload into the payload of the selected points
+    pub async fn set_payload_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: SetPayload,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg = PointsRequest::SetPayloadWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::SetPayload(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// overwrite point payload, replacing it entirely instead of merging keys
+    pub async fn overwrite_payload(
+        &self,
+        collection_name: impl Into<String>,
+        data: SetPayload,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.overwrite_payload_with_options(collection_name, data, WriteOptions::default())
+            .await
+    }
+
+    /// replace the whole payload of the selected points
+    pub async fn overwrite_payload_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: SetPayload,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg =
+            PointsRequest::OverwritePayloadWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::OverwritePayload(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// delete point payload
+    pub async fn delete_payload(
+        &self,
+        collection_name: impl Into<String>,
+        data: DeletePayload,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.delete_payload_with_options(collection_name, data, WriteOptions::default())
+            .await
+    }
+
+    /// remove the given keys from the payload of the selected points
+    pub async fn delete_payload_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: DeletePayload,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg = PointsRequest::DeletePayloadWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::DeletePayload(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// clear point payload
+    pub async fn clear_payload(
+        &self,
+        collection_name: impl Into<String>,
+        points: PointsSelector,
+    ) -> Result<UpdateResult, QdrantError> {
+        self.clear_payload_with_options(collection_name, points, WriteOptions::default())
+            .await
+    }
+
+    /// drop the whole payload of the selected points
+    pub async fn clear_payload_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        points: PointsSelector,
+        options: WriteOptions,
+    ) -> Result<UpdateResult, QdrantError> {
+        let msg = PointsRequest::ClearPayloadWithOptions((collection_name.into(), points, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Points(PointsResponse::ClearPayload(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// search for vectors
+    pub async fn search_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchRequest,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        self.search_points_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// search for vectors with the given [`ReadOptions`]
+    pub async fn search_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        let collection_name = collection_name.into();
+        self.check_query_vector(&collection_name, &data.search_request.vector)?;
+        let cancel = options.cancel.clone();
+        let msg = QueryRequest::SearchWithOptions((collection_name, data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
+            Ok(QdrantResponse::Query(QueryResponse::Search(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    // search for vectors in batch, an empty batch returns an empty result
+    pub async fn search_points_batch(
+        &self,
+        collection_name: impl Into<String>,
+        data: Vec<SearchRequest>,
+    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        self.search_points_batch_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// search for vectors in batch with the given [`ReadOptions`]
+    pub async fn search_points_batch_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: Vec<SearchRequest>,
+        options: ReadOptions,
+    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        let collection_name = collection_name.into();
+        for req in &data {
+            self.check_query_vector(&collection_name, &req.search_request.vector)?;
+        }
+        let data = SearchRequestBatch { searches: data };
+        let msg = QueryRequest::SearchBatchWithOptions((collection_name, data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Query(QueryResponse::SearchBatch(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
         }
     }
 
@@ -354,22 +1542,83 @@ impl QdrantClient {
         collection_name: impl Into<String>,
         data: SearchGroupsRequest,
     ) -> Result<Vec<PointGroup>, QdrantError> {
-        let msg = QueryRequest::SearchGroup((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
+        self.search_points_group_by_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// search points group by with the given [`ReadOptions`]
+    pub async fn search_points_group_by_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchGroupsRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<PointGroup>, QdrantError> {
+        let collection_name = collection_name.into();
+        self.check_query_vector(&collection_name, &data.search_group_request.vector)?;
+        let msg = QueryRequest::SearchGroupWithOptions((collection_name, data, options));
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Query(QueryResponse::SearchGroup(v))) => Ok(v.groups),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// search for vectors, keeping only the best hit per distinct value of `dedup_key`
+    ///
+    /// This is a group-by search with a group size of one, flattened back into a list
+    /// ordered by score. Useful when points are chunks of the same document.
+    pub async fn search_dedup(
+        &self,
+        collection_name: impl Into<String>,
+        vector: impl Into<NamedVectorStruct>,
+        limit: usize,
+        dedup_key: impl Into<String>,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        let data = SearchGroupsRequest {
+            search_group_request: SearchGroupsRequestInternal {
+                vector: vector.into(),
+                filter: None,
+                params: None,
+                with_payload: Some(WithPayloadInterface::Bool(true)),
+                with_vector: None,
+                score_threshold: None,
+                group_request: BaseGroupRequest {
+                    group_by: dedup_key.into(),
+                    group_size: 1,
+                    limit: limit as u32,
+                    with_lookup: None,
+                },
+            },
+            shard_key: None,
+        };
+        let groups = self.search_points_group_by(collection_name, data).await?;
+        let res = groups
+            .into_iter()
+            .filter_map(|group| group.hits.into_iter().next())
+            .collect();
+        Ok(res)
+    }
+
     /// recommend result
     pub async fn recommend_points(
         &self,
         collection_name: impl Into<String>,
         data: RecommendRequest,
     ) -> Result<Vec<ScoredPoint>, QdrantError> {
-        let msg = QueryRequest::Recommend((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
+        self.recommend_points_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// recommend result with the given [`ReadOptions`]
+    pub async fn recommend_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: RecommendRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        let cancel = options.cancel.clone();
+        let msg = QueryRequest::RecommendWithOptions((collection_name.into(), data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
             Ok(QdrantResponse::Query(QueryResponse::Recommend(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
@@ -377,43 +1626,834 @@ impl QdrantClient {
     }
 
     /// recommend batch
+    ///
+    /// Each request is routed by its own `shard_key`, so a single batch can target
+    /// several tenant shards. Results are returned in the order of the requests, and an
+    /// empty batch returns an empty result.
     pub async fn recommend_points_batch(
         &self,
         collection_name: impl Into<String>,
         data: Vec<RecommendRequest>,
     ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        self.recommend_points_batch_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// recommend batch with the given [`ReadOptions`]
+    pub async fn recommend_points_batch_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: Vec<RecommendRequest>,
+        options: ReadOptions,
+    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
         let data = RecommendRequestBatch { searches: data };
-        let msg = QueryRequest::RecommendBatch((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
+        let msg = QueryRequest::RecommendBatchWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Query(QueryResponse::RecommendBatch(v))) => Ok(v),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
 
+    /// recommend batch where every request targets the given shard key
+    ///
+    /// A `None` shard key searches all shards, as with an unsharded request.
+    pub async fn recommend_points_batch_by_shard_key(
+        &self,
+        collection_name: impl Into<String>,
+        data: Vec<(Option<ShardKeySelector>, RecommendRequestInternal)>,
+    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        let data = data
+            .into_iter()
+            .map(|(shard_key, recommend_request)| RecommendRequest {
+                recommend_request,
+                shard_key,
+            })
+            .collect();
+        self.recommend_points_batch(collection_name, data).await
+    }
+
     /// recommend group by
     pub async fn recommend_points_group_by(
         &self,
         collection_name: impl Into<String>,
         data: RecommendGroupsRequest,
     ) -> Result<Vec<PointGroup>, QdrantError> {
-        let msg = QueryRequest::RecommendGroup((collection_name.into(), data));
-        match send_request(&self.tx, msg.into()).await {
+        self.recommend_points_group_by_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// recommend group by with the given [`ReadOptions`]
+    pub async fn recommend_points_group_by_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: RecommendGroupsRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<PointGroup>, QdrantError> {
+        let msg = QueryRequest::RecommendGroupWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
             Ok(QdrantResponse::Query(QueryResponse::RecommendGroup(v))) => Ok(v.groups),
             Err(e) => Err(e),
             res => panic!("Unexpected response: {:?}", res),
         }
     }
+
+    /// discover result
+    pub async fn discover_points(
+        &self,
+        collection_name: impl Into<String>,
+        data: DiscoverRequest,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        self.discover_points_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// discover result with the given [`ReadOptions`]
+    pub async fn discover_points_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: DiscoverRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<ScoredPoint>, QdrantError> {
+        let msg = QueryRequest::DiscoverWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Query(QueryResponse::Discover(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// discover batch
+    pub async fn discover_points_batch(
+        &self,
+        collection_name: impl Into<String>,
+        data: Vec<DiscoverRequest>,
+    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        self.discover_points_batch_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// discover batch with the given [`ReadOptions`]
+    pub async fn discover_points_batch_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: Vec<DiscoverRequest>,
+        options: ReadOptions,
+    ) -> Result<Vec<Vec<ScoredPoint>>, QdrantError> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+        let data = DiscoverRequestBatch { searches: data };
+        let msg = QueryRequest::DiscoverBatchWithOptions((collection_name.into(), data, options));
+        match send_request(&self.sender(), msg.into()).await {
+            Ok(QdrantResponse::Query(QueryResponse::DiscoverBatch(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Whether the worker thread is still running. Once it has exited, cleanly or with an
+    /// error, requests fail with `WorkerUnavailable` until [`QdrantClient::restart`].
+    pub fn is_alive(&self) -> bool {
+        self.worker.read().unwrap().is_alive()
+    }
+
+    /// Count the distinct values of a top-level payload field among the points matching the
+    /// filter, returning the `limit` most frequent ones.
+    ///
+    /// The bundled engine has no facet index, so the worker scans the payload of every
+    /// matching point. Narrow it down with a filter on large collections.
+    pub async fn facet(
+        &self,
+        collection_name: impl Into<String>,
+        key: impl Into<String>,
+        filter: Option<Filter>,
+        limit: usize,
+    ) -> Result<Vec<FacetHit>, QdrantError> {
+        self.facet_with_options(collection_name, key, filter, limit, ReadOptions::default())
+            .await
+    }
+
+    /// facet with the given [`ReadOptions`]
+    pub async fn facet_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        key: impl Into<String>,
+        filter: Option<Filter>,
+        limit: usize,
+        options: ReadOptions,
+    ) -> Result<Vec<FacetHit>, QdrantError> {
+        let data = FacetRequest {
+            key: key.into(),
+            filter,
+            limit,
+        };
+        let cancel = options.cancel.clone();
+        let msg = QueryRequest::Facet((collection_name.into(), data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
+            Ok(QdrantResponse::Query(QueryResponse::Facet(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Pairwise scores within a sample of the points matching the filter: for each of the
+    /// first `sample` points, its `limit` nearest neighbours among the sample.
+    pub async fn search_matrix_pairs(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchMatrixRequest,
+    ) -> Result<Vec<SearchMatrixPair>, QdrantError> {
+        self.search_matrix_pairs_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// search matrix pairs with the given [`ReadOptions`]
+    pub async fn search_matrix_pairs_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchMatrixRequest,
+        options: ReadOptions,
+    ) -> Result<Vec<SearchMatrixPair>, QdrantError> {
+        Ok(self
+            .search_matrix(collection_name, data, options)
+            .await?
+            .pairs)
+    }
+
+    /// Same as `search_matrix_pairs`, as a sparse matrix indexing into the sampled ids.
+    pub async fn search_matrix_offsets(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchMatrixRequest,
+    ) -> Result<SearchMatrixOffsets, QdrantError> {
+        self.search_matrix_offsets_with_options(collection_name, data, ReadOptions::default())
+            .await
+    }
+
+    /// search matrix offsets with the given [`ReadOptions`]
+    pub async fn search_matrix_offsets_with_options(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchMatrixRequest,
+        options: ReadOptions,
+    ) -> Result<SearchMatrixOffsets, QdrantError> {
+        Ok(self
+            .search_matrix(collection_name, data, options)
+            .await?
+            .into())
+    }
+
+    async fn search_matrix(
+        &self,
+        collection_name: impl Into<String>,
+        data: SearchMatrixRequest,
+        options: ReadOptions,
+    ) -> Result<SearchMatrixResult, QdrantError> {
+        let cancel = options.cancel.clone();
+        let msg = QueryRequest::SearchMatrix((collection_name.into(), data, options));
+        match send_request_cancellable(&self.sender(), msg.into(), cancel).await {
+            Ok(QdrantResponse::Query(QueryResponse::SearchMatrix(v))) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Restart the worker thread if it has terminated.
+    ///
+    /// A new worker is started over the same storage and takes over all further requests.
+    /// Returns `false` and leaves the current worker untouched if it is still running.
+    /// Background TTL tasks are bound to the old worker and need to be enabled again.
+    pub fn restart(&self) -> Result<bool, QdrantError> {
+        let mut worker = self.worker.write().unwrap();
+        if worker.is_alive() {
+            return Ok(false);
+        }
+        let new = Worker::spawn(self.settings.clone(), self.stats.clone(), &self.options)?;
+        let old = mem::replace(&mut *worker, new);
+        drop(worker);
+        drop(old);
+        Ok(true)
+    }
+
+    /// Check that the worker thread is alive and the ToC responds within a second.
+    pub async fn health_check(&self) -> Result<(), QdrantError> {
+        if !self.is_alive() {
+            return Err(QdrantError::WorkerUnavailable);
+        }
+        let req = send_request(&self.sender(), QdrantRequest::Health);
+        match time::timeout(HEALTH_CHECK_TIMEOUT, req).await {
+            Ok(Ok(QdrantResponse::Health)) => Ok(()),
+            Ok(Err(e)) => Err(e),
+            Ok(res) => panic!("Unexpected response: {:?}", res),
+            Err(_) => Err(QdrantError::Timeout(format!(
+                "qdrant didn't respond within {HEALTH_CHECK_TIMEOUT:?}"
+            ))),
+        }
+    }
+
+    /// Put the storage in read-only mode, e.g. during a backup, or back to normal. Returns
+    /// whether it was read-only before.
+    ///
+    /// While it's on, every request changing collections, aliases or points fails with
+    /// `QdrantError::ReadOnly`. Reads, searches and creating snapshots keep working.
+    pub async fn set_read_only(&self, enabled: bool) -> Result<bool, QdrantError> {
+        match send_request(&self.sender(), QdrantRequest::SetReadOnly(enabled)).await {
+            Ok(QdrantResponse::SetReadOnly(v)) => Ok(v),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Measure the round trip through the channel and the worker, without any storage work.
+    pub async fn ping(&self) -> Result<Duration, QdrantError> {
+        let start = Instant::now();
+        match send_request(&self.sender(), QdrantRequest::Ping).await {
+            Ok(QdrantResponse::Ping) => Ok(start.elapsed()),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// Parse a JSON encoded [`QdrantRequest`], dispatch it and return the JSON encoded
+    /// [`QdrantResponse`], e.g. to serve requests from an HTTP body.
+    ///
+    /// The request goes straight to the worker, bypassing the client-side checks of the typed
+    /// methods. Cached collection configs are dropped when a collection changes.
+    pub async fn dispatch_json(&self, body: &str) -> Result<String, QdrantError> {
+        let req: QdrantRequest =
+            serde_json::from_str(body).map_err(|e| QdrantError::BadInput(e.to_string()))?;
+        let res = self.dispatch(req).await?;
+        Ok(serde_json::to_string(&res)?)
+    }
+
+    /// send an untyped request, dropping the cached config of a collection it changes
+    ///
+    /// The request gets the same client-side validation as the typed methods.
+    pub(crate) async fn dispatch(&self, req: QdrantRequest) -> Result<QdrantResponse, QdrantError> {
+        self.validate_request(&req)?;
+        let changed = match &req {
+            QdrantRequest::Collection(
+                CollectionRequest::Create((name, _))
+                | CollectionRequest::Update((name, _))
+                | CollectionRequest::Delete(name)
+                | CollectionRequest::DeleteWithOptions((name, _)),
+            ) => Some(name.clone()),
+            _ => None,
+        };
+        let mut res = send_request(&self.sender(), req).await?;
+        if let Some(name) = changed {
+            self.vectors_configs.lock().unwrap().remove(&name);
+        }
+        if let QdrantResponse::Telemetry(data) = &mut res {
+            data.requests = self.stats.snapshot();
+        }
+        Ok(res)
+    }
+
+    /// Telemetry of the instance, e.g. to export into your own metrics pipeline.
+    ///
+    /// Level 0 has the system info, the request stats and aggregated data per collection,
+    /// level 1 and above adds the details of shards and segments.
+    pub async fn telemetry(&self, level: usize) -> Result<TelemetryData, QdrantError> {
+        match send_request(&self.sender(), QdrantRequest::Telemetry(level)).await {
+            Ok(QdrantResponse::Telemetry(v)) => Ok(TelemetryData {
+                requests: self.stats.snapshot(),
+                ..v
+            }),
+            Err(e) => Err(e),
+            res => panic!("Unexpected response: {:?}", res),
+        }
+    }
+
+    /// rolling throughput and latency per operation type
+    pub fn stats_snapshot(&self) -> StatsSnapshot {
+        self.stats.snapshot()
+    }
+
+    /// Log a warning with the operation, collection and elapsed time for every request taking
+    /// longer than `threshold`, `Duration::ZERO` turns it off. Overrides
+    /// [`Settings::slow_query_threshold_ms`] and applies to requests completing from now on.
+    pub fn set_slow_query_threshold(&self, threshold: Duration) {
+        self.stats.set_slow_threshold(Some(threshold));
+    }
+
+    /// the settings the instance was started with, after merging defaults, files and env
+    pub fn effective_settings(&self) -> Settings {
+        self.settings.clone()
+    }
+
+    /// Shut down the instance, waiting until the ToC is dropped and its data flushed.
+    ///
+    /// Requests already queued, or sent by other clones of the channel before it closes, are
+    /// still handled and answered before the ToC is dropped.
+    ///
+    /// Prefer this over dropping the client, which blocks the dropping thread - possibly an
+    /// async executor thread - while qdrant terminates. As the client is shared in an `Arc`,
+    /// get it back with `Arc::try_unwrap` first.
+    pub async fn shutdown(self) -> Result<(), QdrantError> {
+        // stop background tasks first so they don't hold the channel open
+        self.abort_ttl_tasks();
+        let terminated_rx = self.worker.write().unwrap().close();
+        if let Some(rx) = terminated_rx {
+            rx.await?;
+        }
+        Ok(())
+    }
+
+    fn abort_ttl_tasks(&self) {
+        for (_, task) in self.ttl_tasks.lock().unwrap().drain() {
+            task.abort();
+        }
+    }
+
+    fn sender(&self) -> mpsc::Sender<QdrantMsg> {
+        self.worker.read().unwrap().sender()
+    }
+
+    /// run the checks the typed methods do before sending the request
+    fn validate_request(&self, req: &QdrantRequest) -> Result<(), QdrantError> {
+        match req {
+            QdrantRequest::Collection(CollectionRequest::Create((name, data))) => {
+                validate_collection_name(name)?;
+                validate_input(data)?;
+                if let Some(quantization) = &data.quantization_config {
+                    validate_quantization(&data.vectors, quantization)?;
+                }
+            }
+            QdrantRequest::Alias(AliasRequest::Create((collection_name, alias_name))) => {
+                validate_collection_name(collection_name)?;
+                validate_collection_name(alias_name)?;
+            }
+            QdrantRequest::Alias(AliasRequest::Rename((_, new_alias_name))) => {
+                validate_collection_name(new_alias_name)?;
+            }
+            QdrantRequest::Alias(AliasRequest::Change(actions)) => {
+                validate_alias_operations(actions)?;
+            }
+            QdrantRequest::Points(
+                PointsRequest::Upsert((name, insert))
+                | PointsRequest::UpsertWithOptions((name, insert, _)),
+            ) => self.validate_points(name, &inserted_points(insert))?,
+            QdrantRequest::Points(PointsRequest::UpdateBatch((name, operations, _))) => {
+                self.validate_update_operations(name, operations)?
+            }
+            QdrantRequest::Query(
+                QueryRequest::Search((name, data))
+                | QueryRequest::SearchWithOptions((name, data, _)),
+            ) => self.check_query_vector(name, &data.search_request.vector)?,
+            QdrantRequest::Query(
+                QueryRequest::SearchBatch((name, data))
+                | QueryRequest::SearchBatchWithOptions((name, data, _)),
+            ) => {
+                for req in &data.searches {
+                    self.check_query_vector(name, &req.search_request.vector)?;
+                }
+            }
+            QdrantRequest::Query(
+                QueryRequest::SearchGroup((name, data))
+                | QueryRequest::SearchGroupWithOptions((name, data, _)),
+            ) => self.check_query_vector(name, &data.search_group_request.vector)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// check the upserts of a batch update like standalone ones
+    fn validate_update_operations(
+        &self,
+        collection_name: &str,
+        operations: &[PointsUpdateOperation],
+    ) -> Result<(), QdrantError> {
+        for op in operations {
+            if let PointsUpdateOperation::Upsert(insert) = op {
+                self.validate_points(collection_name, &inserted_points(insert))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// check vectors, payload schema and payload size of points about to be upserted
+    fn validate_points(
+        &self,
+        collection_name: &str,
+        points: &[PointStruct],
+    ) -> Result<(), QdrantError> {
+        if let Some(config) = self.vectors_configs.lock().unwrap().get(collection_name) {
+            for point in points {
+                validate_point_vectors(config, point)?;
+            }
+        }
+        if let Some(schema) = self.payload_schemas.lock().unwrap().get(collection_name) {
+            for point in points {
+                schema.validate(&point.id, point.payload.as_ref())?;
+            }
+        }
+        let limit = self.max_payload_bytes.load(Ordering::Relaxed);
+        if limit > 0 {
+            for point in points {
+                validate_payload_size(point, limit)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// reject empty query vectors, and wrong-sized ones if the collection config is known
+    fn check_query_vector(
+        &self,
+        collection_name: &str,
+        vector: &NamedVectorStruct,
+    ) -> Result<(), QdrantError> {
+        let (name, vector) = match vector {
+            NamedVectorStruct::Default(v) => (DEFAULT_VECTOR_NAME, v),
+            NamedVectorStruct::Dense(v) => (v.name.as_str(), &v.vector),
+            NamedVectorStruct::Sparse(_) => return Ok(()),
+        };
+        if vector.is_empty() {
+            return Err(QdrantError::BadInput(format!(
+                "query vector {name:?} for collection {collection_name} is empty"
+            )));
+        }
+        let configs = self.vectors_configs.lock().unwrap();
+        let expected = match configs.get(collection_name) {
+            Some(VectorsConfig::Single(params)) if name == DEFAULT_VECTOR_NAME => params.size,
+            Some(VectorsConfig::Multi(params)) => match params.get(name) {
+                Some(params) => params.size,
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        if vector.len() as u64 != expected.get() {
+            return Err(QdrantError::BadInput(format!(
+                "query vector {name:?} for collection {collection_name} has dimension {}, expected {expected}",
+                vector.len()
+            )));
+        }
+        Ok(())
+    }
 }
 
-async fn send_request(
+pub(crate) async fn send_request(
     sender: &mpsc::Sender<QdrantMsg>,
     msg: QdrantRequest,
 ) -> Result<QdrantResponse, QdrantError> {
     let (tx, rx) = oneshot::channel::<QdrantResult>();
-    if let Err(e) = sender.send((msg, tx)).await {
-        warn!("Failed to send request: {:?}", e);
+    if sender.send((msg, tx)).await.is_err() {
+        // the worker thread is gone, nothing will ever answer
+        return Err(QdrantError::WorkerUnavailable);
     }
     let ret = rx.await?;
     Ok::<_, QdrantError>(ret?)
 }
+
+/// like `send_request`, but stops waiting once the token is cancelled. Dropping the response
+/// receiver tells the worker to abandon the request.
+async fn send_request_cancellable(
+    sender: &mpsc::Sender<QdrantMsg>,
+    msg: QdrantRequest,
+    cancel: Option<CancellationToken>,
+) -> Result<QdrantResponse, QdrantError> {
+    let Some(cancel) = cancel else {
+        return send_request(sender, msg).await;
+    };
+    tokio::select! {
+        res = send_request(sender, msg) => res,
+        _ = cancel.cancelled() => Err(QdrantError::Cancelled),
+    }
+}
+
+fn is_already_exists(e: &QdrantError) -> bool {
+    matches!(e, QdrantError::Storage(StorageError::AlreadyExists { .. }))
+}
+
+fn collection_not_found(name: &str) -> QdrantError {
+    QdrantError::Storage(StorageError::NotFound {
+        description: format!("Collection `{name}` doesn't exist!"),
+    })
+}
+
+/// check the collection and alias names of alias operations
+fn validate_alias_operations(actions: &[AliasOperations]) -> Result<(), QdrantError> {
+    for action in actions {
+        match action {
+            AliasOperations::CreateAlias(op) => {
+                validate_collection_name(&op.create_alias.collection_name)?;
+                validate_collection_name(&op.create_alias.alias_name)?;
+            }
+            AliasOperations::RenameAlias(op) => {
+                validate_collection_name(&op.rename_alias.new_alias_name)?;
+            }
+            AliasOperations::DeleteAlias(_) => {}
+        }
+    }
+    Ok(())
+}
+
+/// Collection and alias names end up as directory names, so reject what Qdrant would: empty
+/// or overly long names, path separators and characters not allowed in file names.
+fn validate_collection_name(name: &str) -> Result<(), QdrantError> {
+    let invalid = name.is_empty()
+        || name.len() > MAX_COLLECTION_NAME_LEN
+        || name
+            .chars()
+            .any(|c| c.is_control() || INVALID_COLLECTION_NAME_CHARS.contains(&c));
+    if invalid {
+        return Err(QdrantError::InvalidCollectionName {
+            name: name.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// run the validation qdrant applies to requests at its API boundary
+fn validate_input(input: &impl Validate) -> Result<(), QdrantError> {
+    input
+        .validate()
+        .map_err(|e| QdrantError::BadInput(e.to_string()))
+}
+
+/// Binary quantization only keeps the sign of each dimension, which preserves angles
+/// but not magnitudes, so it is rejected for distance based metrics.
+fn validate_quantization(
+    vectors: &VectorsConfig,
+    config: &QuantizationConfig,
+) -> Result<(), QdrantError> {
+    if !matches!(config, QuantizationConfig::Binary(_)) {
+        return Ok(());
+    }
+
+    let params: Vec<_> = match vectors {
+        VectorsConfig::Single(params) => vec![params],
+        VectorsConfig::Multi(params) => params.values().collect(),
+    };
+    match params
+        .into_iter()
+        .find(|p| matches!(p.distance, Distance::Euclid | Distance::Manhattan))
+    {
+        Some(p) => Err(QdrantError::BadInput(format!(
+            "Binary quantization is not supported for {:?} distance",
+            p.distance
+        ))),
+        None => Ok(()),
+    }
+}
+
+fn validate_payload_size(point: &PointStruct, limit: usize) -> Result<(), QdrantError> {
+    let Some(payload) = &point.payload else {
+        return Ok(());
+    };
+    let size = serde_json::to_vec(payload)
+        .map_err(|e| QdrantError::BadInput(e.to_string()))?
+        .len();
+    if size > limit {
+        return Err(QdrantError::PayloadTooLarge {
+            point_id: point.id.to_string(),
+            size,
+            limit,
+        });
+    }
+    Ok(())
+}
+
+/// the points of an upsert, with the columnar form split into points so both are checked alike
+fn inserted_points(insert: &PointInsertOperations) -> Cow<'_, [PointStruct]> {
+    let batch = match insert {
+        PointInsertOperations::PointsList(list) => return Cow::Borrowed(&list.points),
+        PointInsertOperations::PointsBatch(batch) => &batch.batch,
+    };
+    let vectors: Vec<VectorStruct> = match &batch.vectors {
+        BatchVectorStruct::Single(vectors) => {
+            vectors.iter().cloned().map(VectorStruct::Single).collect()
+        }
+        BatchVectorStruct::Multi(named) => (0..batch.ids.len())
+            .map(|i| {
+                let vectors = named
+                    .iter()
+                    .filter_map(|(name, vectors)| Some((name.clone(), vectors.get(i)?.clone())))
+                    .collect();
+                VectorStruct::Multi(vectors)
+            })
+            .collect(),
+    };
+    let points = batch
+        .ids
+        .iter()
+        .zip(vectors)
+        .enumerate()
+        .map(|(i, (id, vector))| PointStruct {
+            id: *id,
+            vector,
+            payload: batch
+                .payloads
+                .as_ref()
+                .and_then(|payloads| payloads.get(i).cloned().flatten()),
+        })
+        .collect();
+    Cow::Owned(points)
+}
+
+/// Check the vectors of a point against the collection config, so that missing, unknown or
+/// wrong-sized vectors are reported by name instead of failing deep in the engine.
+fn validate_point_vectors(config: &VectorsConfig, point: &PointStruct) -> Result<(), QdrantError> {
+    let vectors: HashMap<&str, Option<usize>> = match &point.vector {
+        VectorStruct::Single(v) => HashMap::from([(DEFAULT_VECTOR_NAME, Some(v.len()))]),
+        VectorStruct::Multi(vectors) => vectors
+            .iter()
+            .map(|(name, v)| {
+                let len = match v {
+                    Vector::Dense(v) => Some(v.len()),
+                    Vector::Sparse(_) => None,
+                };
+                (name.as_str(), len)
+            })
+            .collect(),
+    };
+    let params: HashMap<&str, &VectorParams> = match config {
+        VectorsConfig::Single(params) => HashMap::from([(DEFAULT_VECTOR_NAME, params)]),
+        VectorsConfig::Multi(params) => params.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+    };
+
+    for (name, params) in &params {
+        match vectors.get(name) {
+            None => {
+                return Err(QdrantError::BadInput(format!(
+                    "point {} is missing vector {name:?}",
+                    point.id
+                )))
+            }
+            Some(Some(len)) if *len as u64 != params.size.get() => {
+                return Err(QdrantError::DimensionMismatch {
+                    point_id: point.id.to_string(),
+                    vector: name.to_string(),
+                    expected: params.size.get() as usize,
+                    got: *len,
+                })
+            }
+            _ => {}
+        }
+    }
+    // sparse vectors are configured separately, only dense ones are checked here
+    if let Some((name, _)) = vectors
+        .iter()
+        .find(|(name, len)| len.is_some() && !params.contains_key(*name))
+    {
+        return Err(QdrantError::BadInput(format!(
+            "point {} has unknown vector {name:?}",
+            point.id
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QdrantInstance;
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn from_json<T: DeserializeOwned>(value: serde_json::Value) -> T {
+        serde_json::from_value(value).unwrap()
+    }
+
+    /// wait for the ToC to be dropped before the temp dir is removed
+    async fn shutdown(client: Arc<QdrantClient>) {
+        let client = Arc::try_unwrap(client).expect("client is still shared");
+        client.shutdown().await.unwrap();
+    }
+
+    /// a temp instance with a `docs` collection of four points
+    async fn client_with_docs() -> Arc<QdrantClient> {
+        let client = QdrantInstance::start_temp().unwrap();
+        client
+            .create_collection("docs", vectors("Cosine"))
+            .await
+            .unwrap();
+        let points = (1..=4)
+            .map(|i| from_json(json!({ "id": i, "vector": [i as f32, 1.0, 0.0, 0.5] })))
+            .collect();
+        let options = WriteOptions {
+            wait: true,
+            ..Default::default()
+        };
+        client
+            .upsert_points_with_options("docs", points, options)
+            .await
+            .unwrap();
+        client
+    }
+
+    fn hits(points: &[ScoredPoint]) -> Vec<(PointIdType, f32)> {
+        points.iter().map(|p| (p.id, p.score)).collect()
+    }
+
+    #[tokio::test]
+    async fn empty_search_batch_skips_the_worker() {
+        let client = QdrantInstance::start_temp().unwrap();
+        let res = client.search_points_batch("docs", vec![]).await.unwrap();
+        assert!(res.is_empty());
+        assert!(client.stats_snapshot().ops.is_empty());
+        shutdown(client).await;
+    }
+
+    #[tokio::test]
+    async fn empty_recommend_batch_skips_the_worker() {
+        let client = QdrantInstance::start_temp().unwrap();
+        let res = client.recommend_points_batch("docs", vec![]).await.unwrap();
+        assert!(res.is_empty());
+        assert!(client.stats_snapshot().ops.is_empty());
+        shutdown(client).await;
+    }
+
+    #[tokio::test]
+    async fn single_search_batch_matches_the_plain_search() {
+        let client = client_with_docs().await;
+        let request: SearchRequest =
+            from_json(json!({ "vector": [1.0, 0.0, 0.0, 0.0], "limit": 3 }));
+        let single = client.search_points("docs", request.clone()).await.unwrap();
+        let batch = client
+            .search_points_batch("docs", vec![request])
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(hits(&batch[0]), hits(&single));
+        shutdown(client).await;
+    }
+
+    #[tokio::test]
+    async fn single_recommend_batch_matches_the_plain_recommend() {
+        let client = client_with_docs().await;
+        let request: RecommendRequest = from_json(json!({ "positive": [1], "limit": 3 }));
+        let single = client
+            .recommend_points("docs", request.clone())
+            .await
+            .unwrap();
+        let batch = client
+            .recommend_points_batch("docs", vec![request])
+            .await
+            .unwrap();
+        assert_eq!(batch.len(), 1);
+        assert_eq!(hits(&batch[0]), hits(&single));
+        shutdown(client).await;
+    }
+
+    fn vectors(distance: &str) -> VectorsConfig {
+        from_json(json!({ "size": 4, "distance": distance }))
+    }
+
+    #[test]
+    fn binary_quantization_is_rejected_for_distance_metrics() {
+        let binary: QuantizationConfig = from_json(json!({ "binary": {} }));
+        for distance in ["Euclid", "Manhattan"] {
+            let err = validate_quantization(&vectors(distance), &binary).unwrap_err();
+            assert!(matches!(err, QdrantError::BadInput(_)));
+        }
+        for distance in ["Cosine", "Dot"] {
+            assert!(validate_quantization(&vectors(distance), &binary).is_ok());
+        }
+    }
+}
@@ -1,6 +1,7 @@
-use std::env;
+use std::{env, time::Duration};
 
 use config::{Config, ConfigError, Environment, File, FileFormat, Source};
+use memory::madvise::Advice;
 use serde::Deserialize;
 use storage::types::StorageConfig;
 use tracing::{error, warn};
@@ -8,6 +9,9 @@ use validator::Validate;
 
 const DEFAULT_CONFIG: &str = include_str!("../config/config.yaml");
 
+/// when set to anything but `0` or `false`, [`Settings::new`] skips the config files
+const SKIP_CONFIG_FILES_ENV: &str = "QDRANT_SKIP_CONFIG_FILES";
+
 #[derive(Debug, Deserialize, Clone, Validate)]
 pub struct Settings {
     #[serde(default = "default_log_level")]
@@ -16,11 +20,95 @@ pub struct Settings {
     pub storage: StorageConfig,
     #[serde(default = "default_telemetry_disabled")]
     pub telemetry_disabled: bool,
+    /// log a warning for every request taking longer than this, off when unset or 0
+    #[serde(default)]
+    pub slow_query_threshold_ms: Option<u64>,
+    /// most requests handled by the worker at once, the others wait their turn, unbounded
+    /// when unset
+    #[serde(default)]
+    pub max_concurrent_requests: Option<usize>,
 }
 
 impl Settings {
-    #[allow(dead_code)]
+    /// Settings built in code, without reading config files or the environment.
+    ///
+    /// ```ignore
+    /// let settings = Settings::from_storage_config(storage).with_log_level("WARN");
+    /// let client = QdrantInstance::start_with_settings(settings)?;
+    /// ```
+    pub fn from_storage_config(storage: StorageConfig) -> Self {
+        Self {
+            log_level: default_log_level(),
+            storage,
+            telemetry_disabled: default_telemetry_disabled(),
+            slow_query_threshold_ms: None,
+            max_concurrent_requests: None,
+        }
+    }
+
+    /// log level of the engine, e.g. `INFO` or `WARN`
+    pub fn with_log_level(mut self, log_level: impl Into<String>) -> Self {
+        self.log_level = log_level.into();
+        self
+    }
+
+    /// the `telemetry_disabled` flag of Qdrant's config; nothing is ever sent from here
+    pub fn with_telemetry_disabled(mut self, telemetry_disabled: bool) -> Self {
+        self.telemetry_disabled = telemetry_disabled;
+        self
+    }
+
+    /// Log a warning with the operation, collection and elapsed time for every request taking
+    /// longer than `threshold`, `Duration::ZERO` turns it off. Can be changed later with
+    /// [`QdrantClient::set_slow_query_threshold`](crate::QdrantClient::set_slow_query_threshold).
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_query_threshold_ms = Some(threshold.as_millis() as u64);
+        self
+    }
+
+    /// Bound the number of requests handled at once, so a burst of expensive searches doesn't
+    /// oversubscribe the runtimes. The requests over the limit wait in the channel, in order.
+    /// Starting fails with `QdrantError::Config` if `max` is 0.
+    pub fn with_max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = Some(max);
+        self
+    }
+
+    /// How the kernel is advised to page in the memory-mapped segments, set process-wide on
+    /// startup.
+    ///
+    /// `Random` avoids read-ahead, which suits the scattered reads of a search and keeps the
+    /// page cache small. `Normal` lets the kernel read ahead, trading memory for fewer page
+    /// faults when vectors are mostly read in order, e.g. on large scrolls.
+    pub fn with_mmap_advice(mut self, advice: Advice) -> Self {
+        self.storage.mmap_advice = advice;
+        self
+    }
+
+    /// Score vectors stored on disk with async io (io_uring, Linux only), set process-wide on
+    /// startup.
+    ///
+    /// Reads of on-disk vectors are then issued concurrently, which lowers search latency on
+    /// fast disks when the vectors don't fit in the page cache. It has no effect on vectors
+    /// kept in memory.
+    pub fn with_async_scorer(mut self, async_scorer: bool) -> Self {
+        self.storage.async_scorer = async_scorer;
+        self
+    }
+
+    /// Load the compiled-in defaults, merged with the config files and the `QDRANT__*`
+    /// environment overrides.
+    ///
+    /// The files are skipped, like [`Settings::new_without_files`], when
+    /// `QDRANT_SKIP_CONFIG_FILES` is set.
     pub fn new(custom_config_path: Option<String>) -> Result<Self, ConfigError> {
+        let skip_files = env::var(SKIP_CONFIG_FILES_ENV)
+            .map(|v| !matches!(v.as_str(), "" | "0" | "false"))
+            .unwrap_or(false);
+        if skip_files {
+            return Self::new_without_files();
+        }
+
         let config_exists = |path| File::with_name(path).collect().is_ok();
 
         // Check if custom config file exists, report error if not
@@ -66,6 +154,18 @@ impl Settings {
         let settings: Settings = config.build()?.try_deserialize()?;
         Ok(settings)
     }
+
+    /// Only the compiled-in defaults and the environment overrides, without looking for any
+    /// config file. Nothing is logged, so embedded startup is silent and doesn't depend on the
+    /// working directory.
+    pub fn new_without_files() -> Result<Self, ConfigError> {
+        let settings: Settings = Config::builder()
+            .add_source(File::from_str(DEFAULT_CONFIG, FileFormat::Yaml))
+            .add_source(Environment::with_prefix("QDRANT").separator("__"))
+            .build()?
+            .try_deserialize()?;
+        Ok(settings)
+    }
 }
 
 fn default_log_level() -> String {
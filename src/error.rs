@@ -1,4 +1,7 @@
 use collection::operations::types::CollectionError;
+use config::ConfigError;
+use serde::{Deserialize, Serialize};
+use std::{error::Error as StdError, io};
 use storage::content_manager::errors::StorageError;
 use thiserror::Error;
 use tokio::sync::oneshot;
@@ -6,9 +9,217 @@ use tokio::sync::oneshot;
 #[derive(Error, Debug)]
 pub enum QdrantError {
     #[error("Collection error: {0}")]
-    Collection(#[from] CollectionError),
+    Collection(CollectionError),
     #[error("Storage error: {0}")]
-    Storage(#[from] StorageError),
+    Storage(StorageError),
+    #[error("Storage is full: {0}")]
+    StorageFull(String),
+    #[error("Storage is read-only: {0}")]
+    ReadOnly(String),
+    #[error("Timeout: {0}")]
+    Timeout(String),
     #[error("Response error: {0}")]
     ResponseRecv(#[from] oneshot::error::RecvError),
+    #[error("Request was cancelled")]
+    Cancelled,
+    #[error("Qdrant worker is unavailable")]
+    WorkerUnavailable,
+    #[error("Failed to spawn thread: {0}")]
+    ThreadSpawn(std::io::Error),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Config error: {0}")]
+    Config(#[from] ConfigError),
+    #[error("Bad input: {0}")]
+    BadInput(String),
+    #[error("Collection `{name}` already exists")]
+    CollectionAlreadyExists { name: String },
+    #[error("Invalid collection name `{name}`: must be 1-255 bytes, without control chars or <>:\"/\\|?*")]
+    InvalidCollectionName { name: String },
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Payload of point {point_id} is {size} bytes, exceeding the limit of {limit} bytes")]
+    PayloadTooLarge {
+        point_id: String,
+        size: usize,
+        limit: usize,
+    },
+    #[error("Vector {vector:?} of point {point_id} has dimension {got}, expected {expected}")]
+    DimensionMismatch {
+        point_id: String,
+        vector: String,
+        expected: usize,
+        got: usize,
+    },
+    #[error("Payload of point {point_id} violates the schema at field `{field}`: {reason}")]
+    SchemaViolation {
+        point_id: String,
+        field: String,
+        reason: String,
+    },
+}
+
+/// Coarse classification of a `QdrantError`, for callers that only care about how to react.
+///
+/// This is a small, stable surface to branch on without depending on the `storage` or
+/// `collection` crates. The original error stays available in the `QdrantError` variant for
+/// details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum QdrantErrorKind {
+    /// the collection, alias or point doesn't exist
+    NotFound,
+    /// the collection or alias already exists
+    AlreadyExists,
+    /// the request is invalid and will fail again if retried as is
+    BadInput,
+    /// the operation didn't finish in time
+    Timeout,
+    /// a temporary failure, e.g. the worker is restarting or writes are locked, retrying may
+    /// succeed
+    Unavailable,
+    /// anything else, a failure of the engine itself
+    ServiceError,
+}
+
+/// The previous name of `QdrantErrorKind`.
+#[deprecated(note = "renamed to `QdrantErrorKind`")]
+pub type ErrorKind = QdrantErrorKind;
+
+// the variant names of the former `ErrorKind`, so matches on them keep compiling
+#[allow(non_upper_case_globals)]
+impl QdrantErrorKind {
+    #[deprecated(note = "use `QdrantErrorKind::AlreadyExists`")]
+    pub const Conflict: Self = Self::AlreadyExists;
+    #[deprecated(note = "use `QdrantErrorKind::Unavailable`")]
+    pub const Transient: Self = Self::Unavailable;
+    #[deprecated(note = "use `QdrantErrorKind::ServiceError`")]
+    pub const Internal: Self = Self::ServiceError;
+}
+
+/// `ENOSPC`, for platforms whose `io::Error` doesn't map it to `ErrorKind::StorageFull`
+const ENOSPC: i32 = 28;
+
+impl From<StorageError> for QdrantError {
+    fn from(e: StorageError) -> Self {
+        match e {
+            StorageError::Timeout { description } => QdrantError::Timeout(description),
+            StorageError::Locked { description } => QdrantError::ReadOnly(description),
+            _ if is_out_of_space(&e) => QdrantError::StorageFull(e.to_string()),
+            _ => QdrantError::Storage(e),
+        }
+    }
+}
+
+impl From<CollectionError> for QdrantError {
+    fn from(e: CollectionError) -> Self {
+        match e {
+            CollectionError::Timeout { description } => QdrantError::Timeout(description),
+            _ if is_out_of_space(&e) => QdrantError::StorageFull(e.to_string()),
+            _ => QdrantError::Collection(e),
+        }
+    }
+}
+
+impl QdrantError {
+    /// Classify the error. Match on the error itself, e.g. `QdrantError::Storage`, for the
+    /// underlying error.
+    pub fn kind(&self) -> QdrantErrorKind {
+        match self {
+            QdrantError::Collection(e) => collection_error_kind(e),
+            QdrantError::Storage(e) => storage_error_kind(e),
+            QdrantError::StorageFull(_) => QdrantErrorKind::ServiceError,
+            QdrantError::Timeout(_) => QdrantErrorKind::Timeout,
+            QdrantError::Cancelled | QdrantError::WorkerUnavailable => QdrantErrorKind::Unavailable,
+            // lifted once the maintenance is over
+            QdrantError::ReadOnly(_) => QdrantErrorKind::Unavailable,
+            // usually a resource limit, which may be lifted
+            QdrantError::ThreadSpawn(_) => QdrantErrorKind::Unavailable,
+            QdrantError::ResponseRecv(_) | QdrantError::Io(_) | QdrantError::Json(_) => {
+                QdrantErrorKind::ServiceError
+            }
+            QdrantError::Config(_) => QdrantErrorKind::BadInput,
+            QdrantError::CollectionAlreadyExists { .. } => QdrantErrorKind::AlreadyExists,
+            QdrantError::BadInput(_)
+            | QdrantError::InvalidCollectionName { .. }
+            | QdrantError::PayloadTooLarge { .. }
+            | QdrantError::SchemaViolation { .. }
+            | QdrantError::DimensionMismatch { .. } => QdrantErrorKind::BadInput,
+        }
+    }
+
+    /// Whether retrying the same request may succeed: it timed out, writes were locked, or the
+    /// storage reported a temporary or internal failure. A stopped worker, a lost response,
+    /// malformed JSON, cancellations and a full disk are not retried.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            QdrantError::Timeout(_) | QdrantError::ReadOnly(_) => true,
+            QdrantError::Storage(_) | QdrantError::Collection(_) => matches!(
+                self.kind(),
+                QdrantErrorKind::Unavailable
+                    | QdrantErrorKind::Timeout
+                    | QdrantErrorKind::ServiceError
+            ),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl From<&QdrantError> for http::StatusCode {
+    fn from(e: &QdrantError) -> Self {
+        if let QdrantError::StorageFull(_) = e {
+            return http::StatusCode::INSUFFICIENT_STORAGE;
+        }
+        match e.kind() {
+            QdrantErrorKind::NotFound => http::StatusCode::NOT_FOUND,
+            QdrantErrorKind::BadInput => http::StatusCode::BAD_REQUEST,
+            QdrantErrorKind::AlreadyExists => http::StatusCode::CONFLICT,
+            QdrantErrorKind::Timeout => http::StatusCode::GATEWAY_TIMEOUT,
+            QdrantErrorKind::Unavailable => http::StatusCode::SERVICE_UNAVAILABLE,
+            QdrantErrorKind::ServiceError => http::StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// whether the error, or any error in its source chain, is the OS reporting a full disk
+fn is_out_of_space(e: &(dyn StdError + 'static)) -> bool {
+    let mut source = Some(e);
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<io::Error>() {
+            if e.kind() == io::ErrorKind::StorageFull || e.raw_os_error() == Some(ENOSPC) {
+                return true;
+            }
+        }
+        source = e.source();
+    }
+    false
+}
+
+fn storage_error_kind(e: &StorageError) -> QdrantErrorKind {
+    match e {
+        StorageError::NotFound { .. } => QdrantErrorKind::NotFound,
+        StorageError::BadInput { .. } | StorageError::BadRequest { .. } => {
+            QdrantErrorKind::BadInput
+        }
+        StorageError::AlreadyExists { .. } => QdrantErrorKind::AlreadyExists,
+        StorageError::Locked { .. } => QdrantErrorKind::Unavailable,
+        StorageError::Timeout { .. } => QdrantErrorKind::Timeout,
+        _ => QdrantErrorKind::ServiceError,
+    }
+}
+
+fn collection_error_kind(e: &CollectionError) -> QdrantErrorKind {
+    match e {
+        CollectionError::NotFound { .. } | CollectionError::PointNotFound { .. } => {
+            QdrantErrorKind::NotFound
+        }
+        CollectionError::BadInput { .. }
+        | CollectionError::BadRequest { .. }
+        | CollectionError::BadShardSelection { .. } => QdrantErrorKind::BadInput,
+        CollectionError::Timeout { .. } => QdrantErrorKind::Timeout,
+        CollectionError::Cancelled { .. }
+        | CollectionError::InconsistentShardFailure { .. }
+        | CollectionError::ForwardProxyError { .. } => QdrantErrorKind::Unavailable,
+        _ => QdrantErrorKind::ServiceError,
+    }
 }
@@ -0,0 +1,1437 @@
+//! A local gRPC server implementing the Qdrant `Points` and `Collections` services.
+//!
+//! Every RPC goes through the matching [`QdrantClient`] method, so it gets the same validation.
+//! These RPCs aren't mapped and answer with `Unimplemented`:
+//!
+//! - `Collections/CollectionClusterInfo` and `Collections/UpdateCollectionClusterSetup`, as an
+//!   embedded instance has no cluster
+//! - sparse vectors, custom field index params and group lookups in the mapped RPCs
+
+use crate::{
+    PointsUpdateOperation, QdrantClient, QdrantError, QdrantErrorKind, ReadOptions, WriteOptions,
+};
+use api::grpc::{
+    conversions::proto_to_payloads,
+    qdrant::{
+        collections_server::{Collections, CollectionsServer},
+        points_server::{Points, PointsServer},
+        points_update_operation as update_op, shard_key, target_vector, vector_example,
+        AliasDescription, BatchResult, ChangeAliases, ClearPayloadPoints,
+        CollectionClusterInfoRequest, CollectionClusterInfoResponse, CollectionDescription,
+        CollectionOperationResponse, CountPoints, CountResponse, CountResult, CreateCollection,
+        CreateFieldIndexCollection, CreateShardKey, CreateShardKeyRequest, CreateShardKeyResponse,
+        DeleteCollection, DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors,
+        DeletePoints, DeleteShardKey, DeleteShardKeyRequest, DeleteShardKeyResponse,
+        DiscoverBatchPoints, DiscoverBatchResponse, DiscoverPoints, DiscoverResponse, FieldType,
+        GetCollectionInfoRequest, GetCollectionInfoResponse, GetPoints, GetResponse, GroupsResult,
+        ListAliasesRequest, ListAliasesResponse, ListCollectionAliasesRequest,
+        ListCollectionsRequest, ListCollectionsResponse, PointsOperationResponse,
+        RecommendBatchPoints, RecommendBatchResponse, RecommendGroupsResponse,
+        RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
+        SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse, SearchPointGroups,
+        SearchPoints, SearchResponse, SetPayloadPoints, UpdateBatchPoints, UpdateBatchResponse,
+        UpdateCollection, UpdateCollectionClusterSetupRequest,
+        UpdateCollectionClusterSetupResponse, UpdatePointVectors, UpsertPoints,
+    },
+};
+use collection::operations::{
+    cluster_ops::CreateShardingKey,
+    consistency_params::ReadConsistency,
+    conversions::write_ordering_from_proto,
+    payload_ops::{DeletePayload, SetPayload},
+    point_ops::{
+        FilterSelector, PointIdsList, PointInsertOperations, PointStruct, PointsList,
+        PointsSelector,
+    },
+    shard_key_selector::ShardKeySelector,
+    types::{
+        BaseGroupRequest, ContextExamplePair, DiscoverRequest, DiscoverRequestInternal,
+        LookupLocation, PointRequest, PointRequestInternal, RecommendExample,
+        RecommendGroupsRequest, RecommendGroupsRequestInternal, RecommendRequest,
+        RecommendRequestInternal, RecommendStrategy, ScrollRequest, ScrollRequestInternal,
+        SearchGroupsRequest, SearchGroupsRequestInternal, SearchRequest, SearchRequestInternal,
+        UsingVector,
+    },
+    vector_ops::{DeleteVectors, PointVectors, UpdateVectors},
+};
+use segment::{
+    data_types::vectors::{NamedVector, NamedVectorStruct},
+    types::{Filter, PayloadFieldSchema, PayloadSchemaType, PointIdType, ScoredPoint, ShardKey},
+};
+use std::{
+    io,
+    net::SocketAddr,
+    num::NonZeroU32,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage::content_manager::collection_meta_ops::CollectionMetaOperations;
+use tonic::{transport::Server, Code, Request, Response, Status};
+
+/// Serve the `Points` and `Collections` services of `client` on `addr` until the server fails.
+pub async fn serve_grpc(client: Arc<QdrantClient>, addr: SocketAddr) -> Result<(), QdrantError> {
+    let service = GrpcService { client };
+    Server::builder()
+        .add_service(PointsServer::new(service.clone()))
+        .add_service(CollectionsServer::new(service))
+        .serve(addr)
+        .await
+        .map_err(|e| QdrantError::Io(io::Error::other(e)))
+}
+
+#[derive(Debug, Clone)]
+struct GrpcService {
+    client: Arc<QdrantClient>,
+}
+
+#[tonic::async_trait]
+impl Collections for GrpcService {
+    async fn get(
+        &self,
+        request: Request<GetCollectionInfoRequest>,
+    ) -> Result<Response<GetCollectionInfoResponse>, Status> {
+        let start = Instant::now();
+        let name = request.into_inner().collection_name;
+        let Some(info) = self.client.get_collection(&name).await.map_err(status)? else {
+            return Err(Status::not_found(format!(
+                "Collection `{name}` doesn't exist!"
+            )));
+        };
+        Ok(Response::new(GetCollectionInfoResponse {
+            result: Some(info.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn list(
+        &self,
+        _request: Request<ListCollectionsRequest>,
+    ) -> Result<Response<ListCollectionsResponse>, Status> {
+        let start = Instant::now();
+        let names = self.client.list_collections().await.map_err(status)?;
+        Ok(Response::new(ListCollectionsResponse {
+            collections: names
+                .into_iter()
+                .map(|name| CollectionDescription { name })
+                .collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn create(
+        &self,
+        request: Request<CreateCollection>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let start = Instant::now();
+        let CollectionMetaOperations::CreateCollection(op) = request.into_inner().try_into()?
+        else {
+            return Err(Status::invalid_argument("Not a collection creation"));
+        };
+        let result = self
+            .client
+            .create_collection_with(op.collection_name, op.create_collection)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(CollectionOperationResponse {
+            result,
+            time: elapsed(start),
+        }))
+    }
+
+    async fn update(
+        &self,
+        request: Request<UpdateCollection>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let start = Instant::now();
+        let CollectionMetaOperations::UpdateCollection(op) = request.into_inner().try_into()?
+        else {
+            return Err(Status::invalid_argument("Not a collection update"));
+        };
+        let result = self
+            .client
+            .update_collection(op.collection_name, op.update_collection)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(CollectionOperationResponse {
+            result,
+            time: elapsed(start),
+        }))
+    }
+
+    async fn delete(
+        &self,
+        request: Request<DeleteCollection>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let start = Instant::now();
+        let name = request.into_inner().collection_name;
+        let result = self.client.delete_collection(name).await.map_err(status)?;
+        Ok(Response::new(CollectionOperationResponse {
+            result,
+            time: elapsed(start),
+        }))
+    }
+
+    async fn update_aliases(
+        &self,
+        request: Request<ChangeAliases>,
+    ) -> Result<Response<CollectionOperationResponse>, Status> {
+        let start = Instant::now();
+        let CollectionMetaOperations::ChangeAliases(op) = request.into_inner().try_into()? else {
+            return Err(Status::invalid_argument("Not an alias change"));
+        };
+        let result = self
+            .client
+            .change_aliases(op.actions)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(CollectionOperationResponse {
+            result,
+            time: elapsed(start),
+        }))
+    }
+
+    async fn list_collection_aliases(
+        &self,
+        request: Request<ListCollectionAliasesRequest>,
+    ) -> Result<Response<ListAliasesResponse>, Status> {
+        let start = Instant::now();
+        let name = request.into_inner().collection_name;
+        let aliases = self.client.get_aliases(name).await.map_err(status)?;
+        Ok(Response::new(list_aliases_response(aliases, start)))
+    }
+
+    async fn list_aliases(
+        &self,
+        _request: Request<ListAliasesRequest>,
+    ) -> Result<Response<ListAliasesResponse>, Status> {
+        let start = Instant::now();
+        let aliases = self.client.list_aliases().await.map_err(status)?;
+        Ok(Response::new(list_aliases_response(aliases, start)))
+    }
+
+    async fn collection_cluster_info(
+        &self,
+        _request: Request<CollectionClusterInfoRequest>,
+    ) -> Result<Response<CollectionClusterInfoResponse>, Status> {
+        Err(unimplemented("Collections/CollectionClusterInfo"))
+    }
+
+    async fn update_collection_cluster_setup(
+        &self,
+        _request: Request<UpdateCollectionClusterSetupRequest>,
+    ) -> Result<Response<UpdateCollectionClusterSetupResponse>, Status> {
+        Err(unimplemented("Collections/UpdateCollectionClusterSetup"))
+    }
+
+    async fn create_shard_key(
+        &self,
+        request: Request<CreateShardKeyRequest>,
+    ) -> Result<Response<CreateShardKeyResponse>, Status> {
+        let CreateShardKeyRequest {
+            collection_name,
+            request,
+            timeout: _,
+        } = request.into_inner();
+        let Some(CreateShardKey {
+            shard_key,
+            shards_number,
+            replication_factor,
+            placement,
+        }) = request
+        else {
+            return Err(Status::invalid_argument("CreateShardKey is missing"));
+        };
+
+        let data = CreateShardingKey {
+            shard_key: shard_key_from_proto(shard_key)?,
+            shards_number: shards_number.and_then(NonZeroU32::new),
+            replication_factor: replication_factor.and_then(NonZeroU32::new),
+            placement: (!placement.is_empty()).then_some(placement),
+        };
+        let result = self
+            .client
+            .create_shard_key(collection_name, data)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(CreateShardKeyResponse { result }))
+    }
+
+    async fn delete_shard_key(
+        &self,
+        request: Request<DeleteShardKeyRequest>,
+    ) -> Result<Response<DeleteShardKeyResponse>, Status> {
+        let DeleteShardKeyRequest {
+            collection_name,
+            request,
+            timeout: _,
+        } = request.into_inner();
+        let Some(DeleteShardKey { shard_key }) = request else {
+            return Err(Status::invalid_argument("DeleteShardKey is missing"));
+        };
+
+        let result = self
+            .client
+            .drop_shard_key(collection_name, shard_key_from_proto(shard_key)?)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(DeleteShardKeyResponse { result }))
+    }
+}
+
+#[tonic::async_trait]
+impl Points for GrpcService {
+    async fn upsert(
+        &self,
+        request: Request<UpsertPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let UpsertPoints {
+            collection_name,
+            wait,
+            points,
+            ordering,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let points = points
+            .into_iter()
+            .map(PointStruct::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = write_options(wait, ordering, shard_key_selector)?;
+        let res = self
+            .client
+            .upsert_points_with_options(collection_name, points, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn delete(
+        &self,
+        request: Request<DeletePoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let DeletePoints {
+            collection_name,
+            wait,
+            points,
+            ordering,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let Some(points) = points else {
+            return Err(Status::invalid_argument("PointSelector is missing"));
+        };
+        let selector: PointsSelector = points.try_into()?;
+        let options = write_options(wait, ordering, shard_key_selector)?;
+        let res = self
+            .client
+            .delete_points_with_options(collection_name, selector, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn get(&self, request: Request<GetPoints>) -> Result<Response<GetResponse>, Status> {
+        let start = Instant::now();
+        let GetPoints {
+            collection_name,
+            ids,
+            with_payload,
+            with_vectors,
+            read_consistency,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let data = PointRequest {
+            point_request: PointRequestInternal {
+                ids: ids
+                    .into_iter()
+                    .map(|id| id.try_into())
+                    .collect::<Result<_, _>>()?,
+                with_payload: with_payload.map(|p| p.try_into()).transpose()?,
+                with_vector: with_vectors.map(|v| v.into()).unwrap_or_default(),
+            },
+            shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+        };
+        let options = read_options(read_consistency, None)?;
+        let records = self
+            .client
+            .get_points_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(GetResponse {
+            result: records.into_iter().map(|r| r.into()).collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn update_vectors(
+        &self,
+        request: Request<UpdatePointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let UpdatePointVectors {
+            collection_name,
+            wait,
+            points,
+            ordering,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let points = points_vectors_from_proto(points)?;
+        let options = write_options(wait, ordering, shard_key_selector)?;
+        let res = self
+            .client
+            .update_vectors_with_options(collection_name, points, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn delete_vectors(
+        &self,
+        request: Request<DeletePointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let DeletePointVectors {
+            collection_name,
+            wait,
+            points_selector,
+            vectors,
+            ordering,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let (points, filter) = points_or_filter(points_selector)?;
+        let Some(vectors) = vectors else {
+            return Err(Status::invalid_argument("VectorsSelector is missing"));
+        };
+        let data = DeleteVectors {
+            points,
+            filter,
+            vector: vectors.names.into_iter().collect(),
+            shard_key: None,
+        };
+        let options = write_options(wait, ordering, shard_key_selector)?;
+        let res = self
+            .client
+            .delete_vectors_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn set_payload(
+        &self,
+        request: Request<SetPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let (collection_name, data, options) = set_payload_from_proto(request.into_inner())?;
+        let res = self
+            .client
+            .set_payload_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn overwrite_payload(
+        &self,
+        request: Request<SetPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let (collection_name, data, options) = set_payload_from_proto(request.into_inner())?;
+        let res = self
+            .client
+            .overwrite_payload_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn delete_payload(
+        &self,
+        request: Request<DeletePayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let DeletePayloadPoints {
+            collection_name,
+            wait,
+            keys,
+            points_selector,
+            ordering,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let (points, filter) = points_or_filter(points_selector)?;
+        let data = DeletePayload {
+            keys,
+            points,
+            filter,
+            shard_key: None,
+        };
+        let options = write_options(wait, ordering, shard_key_selector)?;
+        let res = self
+            .client
+            .delete_payload_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn clear_payload(
+        &self,
+        request: Request<ClearPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let ClearPayloadPoints {
+            collection_name,
+            wait,
+            points,
+            ordering,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let Some(points) = points else {
+            return Err(Status::invalid_argument("PointSelector is missing"));
+        };
+        let selector: PointsSelector = points.try_into()?;
+        let options = write_options(wait, ordering, shard_key_selector)?;
+        let res = self
+            .client
+            .clear_payload_with_options(collection_name, selector, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn update_batch(
+        &self,
+        request: Request<UpdateBatchPoints>,
+    ) -> Result<Response<UpdateBatchResponse>, Status> {
+        let start = Instant::now();
+        let UpdateBatchPoints {
+            collection_name,
+            wait,
+            operations,
+            ordering,
+        } = request.into_inner();
+
+        let operations = operations
+            .into_iter()
+            .map(update_operation_from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = write_options(wait, ordering, None)?;
+        let res = self
+            .client
+            .batch_update_with_options(collection_name, operations, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(UpdateBatchResponse {
+            result: res.into_iter().map(|r| r.into()).collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn create_field_index(
+        &self,
+        request: Request<CreateFieldIndexCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let CreateFieldIndexCollection {
+            collection_name,
+            wait: _,
+            field_name,
+            field_type,
+            field_index_params,
+            ordering: _,
+        } = request.into_inner();
+        if field_index_params.is_some() {
+            return Err(unimplemented("custom field index params"));
+        }
+
+        let schema = match field_type.map(FieldType::try_from) {
+            Some(Ok(FieldType::Keyword)) => PayloadSchemaType::Keyword,
+            Some(Ok(FieldType::Integer)) => PayloadSchemaType::Integer,
+            Some(Ok(FieldType::Float)) => PayloadSchemaType::Float,
+            Some(Ok(FieldType::Geo)) => PayloadSchemaType::Geo,
+            Some(Ok(FieldType::Text)) => PayloadSchemaType::Text,
+            Some(Ok(FieldType::Bool)) => PayloadSchemaType::Bool,
+            Some(Err(_)) => return Err(Status::invalid_argument("Unknown field type")),
+            None => return Err(Status::invalid_argument("Field type is missing")),
+        };
+        let res = self
+            .client
+            .create_field_index(
+                collection_name,
+                field_name,
+                PayloadFieldSchema::FieldType(schema),
+            )
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn delete_field_index(
+        &self,
+        request: Request<DeleteFieldIndexCollection>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        let start = Instant::now();
+        let DeleteFieldIndexCollection {
+            collection_name,
+            wait: _,
+            field_name,
+            ordering: _,
+        } = request.into_inner();
+
+        let res = self
+            .client
+            .delete_field_index(collection_name, field_name)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(PointsOperationResponse {
+            result: Some(res.into()),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn search(
+        &self,
+        request: Request<SearchPoints>,
+    ) -> Result<Response<SearchResponse>, Status> {
+        let start = Instant::now();
+        let request = request.into_inner();
+        let collection_name = request.collection_name.clone();
+        let options = read_options(request.read_consistency.clone(), request.timeout)?;
+        let data = search_request_from_proto(request)?;
+        let points = self
+            .client
+            .search_points_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(SearchResponse {
+            result: points.into_iter().map(|p| p.into()).collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn search_batch(
+        &self,
+        request: Request<SearchBatchPoints>,
+    ) -> Result<Response<SearchBatchResponse>, Status> {
+        let start = Instant::now();
+        let SearchBatchPoints {
+            collection_name,
+            search_points,
+            read_consistency,
+            timeout,
+        } = request.into_inner();
+
+        let data = search_points
+            .into_iter()
+            .map(search_request_from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = read_options(read_consistency, timeout)?;
+        let res = self
+            .client
+            .search_points_batch_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(SearchBatchResponse {
+            result: batch_results(res),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn search_groups(
+        &self,
+        request: Request<SearchPointGroups>,
+    ) -> Result<Response<SearchGroupsResponse>, Status> {
+        let start = Instant::now();
+        let SearchPointGroups {
+            collection_name,
+            vector,
+            filter,
+            limit,
+            with_payload,
+            params,
+            score_threshold,
+            vector_name,
+            with_vectors,
+            group_by,
+            group_size,
+            read_consistency,
+            with_lookup,
+            timeout,
+            shard_key_selector,
+            sparse_indices,
+        } = request.into_inner();
+        if sparse_indices.is_some() {
+            return Err(unimplemented("sparse vector search"));
+        }
+        if with_lookup.is_some() {
+            return Err(unimplemented("group lookups"));
+        }
+
+        let data = SearchGroupsRequest {
+            search_group_request: SearchGroupsRequestInternal {
+                vector: named_vector(vector_name, vector),
+                filter: filter.map(|f| f.try_into()).transpose()?,
+                params: params.map(|p| p.into()),
+                with_payload: with_payload.map(|p| p.try_into()).transpose()?,
+                with_vector: with_vectors.map(|v| v.into()),
+                score_threshold,
+                group_request: BaseGroupRequest {
+                    group_by: group_by.into(),
+                    group_size,
+                    limit,
+                    with_lookup: None,
+                },
+            },
+            shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+        };
+        let options = read_options(read_consistency, timeout)?;
+        let groups = self
+            .client
+            .search_points_group_by_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(SearchGroupsResponse {
+            result: Some(GroupsResult {
+                groups: groups.into_iter().map(|g| g.into()).collect(),
+            }),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn scroll(
+        &self,
+        request: Request<ScrollPoints>,
+    ) -> Result<Response<ScrollResponse>, Status> {
+        let start = Instant::now();
+        let ScrollPoints {
+            collection_name,
+            filter,
+            offset,
+            limit,
+            with_payload,
+            with_vectors,
+            read_consistency,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let data = ScrollRequest {
+            scroll_request: ScrollRequestInternal {
+                offset: offset.map(|o| o.try_into()).transpose()?,
+                limit: limit.map(|l| l as usize),
+                filter: filter.map(|f| f.try_into()).transpose()?,
+                with_payload: with_payload.map(|p| p.try_into()).transpose()?,
+                with_vector: with_vectors.map(|v| v.into()).unwrap_or_default(),
+            },
+            shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+        };
+        let options = read_options(read_consistency, None)?;
+        let res = self
+            .client
+            .scroll_points_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(ScrollResponse {
+            next_page_offset: res.next_page_offset.map(|id| id.into()),
+            result: res.points.into_iter().map(|r| r.into()).collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn recommend(
+        &self,
+        request: Request<RecommendPoints>,
+    ) -> Result<Response<RecommendResponse>, Status> {
+        let start = Instant::now();
+        let request = request.into_inner();
+        let collection_name = request.collection_name.clone();
+        let options = read_options(request.read_consistency.clone(), request.timeout)?;
+        let data = recommend_request_from_proto(request)?;
+        let points = self
+            .client
+            .recommend_points_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(RecommendResponse {
+            result: points.into_iter().map(|p| p.into()).collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn recommend_batch(
+        &self,
+        request: Request<RecommendBatchPoints>,
+    ) -> Result<Response<RecommendBatchResponse>, Status> {
+        let start = Instant::now();
+        let RecommendBatchPoints {
+            collection_name,
+            recommend_points,
+            read_consistency,
+            timeout,
+        } = request.into_inner();
+
+        let data = recommend_points
+            .into_iter()
+            .map(recommend_request_from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = read_options(read_consistency, timeout)?;
+        let res = self
+            .client
+            .recommend_points_batch_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(RecommendBatchResponse {
+            result: batch_results(res),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn recommend_groups(
+        &self,
+        request: Request<RecommendPointGroups>,
+    ) -> Result<Response<RecommendGroupsResponse>, Status> {
+        let start = Instant::now();
+        let RecommendPointGroups {
+            collection_name,
+            positive,
+            negative,
+            filter,
+            limit,
+            with_payload,
+            params,
+            score_threshold,
+            using,
+            with_vectors,
+            lookup_from,
+            group_by,
+            group_size,
+            read_consistency,
+            with_lookup,
+            strategy,
+            positive_vectors,
+            negative_vectors,
+            timeout,
+            shard_key_selector,
+        } = request.into_inner();
+        if with_lookup.is_some() {
+            return Err(unimplemented("group lookups"));
+        }
+
+        // the examples convert like a plain recommendation
+        let RecommendRequest {
+            recommend_request: recommend,
+            shard_key,
+        } = recommend_request_from_proto(RecommendPoints {
+            collection_name: collection_name.clone(),
+            positive,
+            negative,
+            filter,
+            limit: limit as u64,
+            with_payload,
+            params,
+            score_threshold,
+            offset: None,
+            using,
+            with_vectors,
+            lookup_from,
+            read_consistency: None,
+            strategy,
+            positive_vectors,
+            negative_vectors,
+            timeout: None,
+            shard_key_selector,
+        })?;
+        let data = RecommendGroupsRequest {
+            recommend_group_request: RecommendGroupsRequestInternal {
+                positive: recommend.positive,
+                negative: recommend.negative,
+                strategy: recommend.strategy,
+                filter: recommend.filter,
+                params: recommend.params,
+                with_payload: recommend.with_payload,
+                with_vector: recommend.with_vector,
+                score_threshold: recommend.score_threshold,
+                using: recommend.using,
+                lookup_from: recommend.lookup_from,
+                group_request: BaseGroupRequest {
+                    group_by: group_by.into(),
+                    group_size,
+                    limit,
+                    with_lookup: None,
+                },
+            },
+            shard_key,
+        };
+        let options = read_options(read_consistency, timeout)?;
+        let groups = self
+            .client
+            .recommend_points_group_by_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(RecommendGroupsResponse {
+            result: Some(GroupsResult {
+                groups: groups.into_iter().map(|g| g.into()).collect(),
+            }),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn discover(
+        &self,
+        request: Request<DiscoverPoints>,
+    ) -> Result<Response<DiscoverResponse>, Status> {
+        let start = Instant::now();
+        let request = request.into_inner();
+        let collection_name = request.collection_name.clone();
+        let options = read_options(request.read_consistency.clone(), request.timeout)?;
+        let data = discover_request_from_proto(request)?;
+        let points = self
+            .client
+            .discover_points_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(DiscoverResponse {
+            result: points.into_iter().map(|p| p.into()).collect(),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn discover_batch(
+        &self,
+        request: Request<DiscoverBatchPoints>,
+    ) -> Result<Response<DiscoverBatchResponse>, Status> {
+        let start = Instant::now();
+        let DiscoverBatchPoints {
+            collection_name,
+            discover_points,
+            read_consistency,
+            timeout,
+        } = request.into_inner();
+
+        let data = discover_points
+            .into_iter()
+            .map(discover_request_from_proto)
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = read_options(read_consistency, timeout)?;
+        let res = self
+            .client
+            .discover_points_batch_with_options(collection_name, data, options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(DiscoverBatchResponse {
+            result: batch_results(res),
+            time: elapsed(start),
+        }))
+    }
+
+    async fn count(
+        &self,
+        request: Request<CountPoints>,
+    ) -> Result<Response<CountResponse>, Status> {
+        let start = Instant::now();
+        let CountPoints {
+            collection_name,
+            filter,
+            exact,
+            read_consistency,
+            shard_key_selector,
+        } = request.into_inner();
+
+        let filter = filter.map(|f| f.try_into()).transpose()?;
+        let options = ReadOptions {
+            shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+            ..read_options(read_consistency, None)?
+        };
+        let count = self
+            .client
+            .count_points_with_options(collection_name, filter, exact.unwrap_or(true), options)
+            .await
+            .map_err(status)?;
+        Ok(Response::new(CountResponse {
+            result: Some(CountResult {
+                count: count as u64,
+            }),
+            time: elapsed(start),
+        }))
+    }
+}
+
+fn write_options(
+    wait: Option<bool>,
+    ordering: Option<api::grpc::qdrant::WriteOrdering>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<WriteOptions, Status> {
+    Ok(WriteOptions {
+        wait: wait.unwrap_or(false),
+        ordering: write_ordering_from_proto(ordering)?,
+        shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+    })
+}
+
+/// read options from the consistency and timeout (in seconds) of a read request
+fn read_options(
+    read_consistency: Option<api::grpc::qdrant::ReadConsistency>,
+    timeout: Option<u64>,
+) -> Result<ReadOptions, Status> {
+    Ok(ReadOptions {
+        consistency: read_consistency
+            .map(ReadConsistency::try_from)
+            .transpose()?,
+        timeout: timeout.map(Duration::from_secs),
+        ..Default::default()
+    })
+}
+
+fn shard_key_from_proto(key: Option<api::grpc::qdrant::ShardKey>) -> Result<ShardKey, Status> {
+    match key.and_then(|key| key.key) {
+        Some(shard_key::Key::Keyword(keyword)) => Ok(ShardKey::Keyword(keyword)),
+        Some(shard_key::Key::Number(number)) => Ok(ShardKey::Number(number)),
+        None => Err(Status::invalid_argument("Shard key is missing")),
+    }
+}
+
+fn shard_key_selector_from_proto(
+    selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<Option<ShardKeySelector>, Status> {
+    let Some(selector) = selector else {
+        return Ok(None);
+    };
+    let mut keys = selector
+        .shard_keys
+        .into_iter()
+        .map(|key| shard_key_from_proto(Some(key)))
+        .collect::<Result<Vec<_>, _>>()?;
+    match keys.len() {
+        0 => Err(Status::invalid_argument("Shard key selector is empty")),
+        1 => Ok(Some(ShardKeySelector::ShardKey(keys.remove(0)))),
+        _ => Ok(Some(ShardKeySelector::ShardKeys(keys))),
+    }
+}
+
+/// split a points selector into the ids or the filter it selects by
+fn points_or_filter(
+    selector: Option<api::grpc::qdrant::PointsSelector>,
+) -> Result<(Option<Vec<PointIdType>>, Option<Filter>), Status> {
+    let Some(selector) = selector else {
+        return Err(Status::invalid_argument("PointSelector is missing"));
+    };
+    match selector.try_into()? {
+        PointsSelector::PointIdsSelector(PointIdsList { points, .. }) => Ok((Some(points), None)),
+        PointsSelector::FilterSelector(FilterSelector { filter, .. }) => Ok((None, Some(filter))),
+    }
+}
+
+fn set_payload_from_proto(
+    request: SetPayloadPoints,
+) -> Result<(String, SetPayload, WriteOptions), Status> {
+    let SetPayloadPoints {
+        collection_name,
+        wait,
+        payload,
+        points_selector,
+        ordering,
+        shard_key_selector,
+    } = request;
+
+    let (points, filter) = points_or_filter(points_selector)?;
+    let data = SetPayload {
+        payload: proto_to_payloads(payload)?,
+        points,
+        filter,
+        shard_key: None,
+    };
+    let options = write_options(wait, ordering, shard_key_selector)?;
+    Ok((collection_name, data, options))
+}
+
+fn named_vector(vector_name: Option<String>, vector: Vec<f32>) -> NamedVectorStruct {
+    match vector_name {
+        Some(name) => NamedVectorStruct::Dense(NamedVector { name, vector }),
+        None => NamedVectorStruct::Default(vector),
+    }
+}
+
+fn search_request_from_proto(request: SearchPoints) -> Result<SearchRequest, Status> {
+    let SearchPoints {
+        collection_name: _,
+        vector,
+        filter,
+        limit,
+        with_payload,
+        params,
+        score_threshold,
+        offset,
+        vector_name,
+        with_vectors,
+        read_consistency: _,
+        timeout: _,
+        shard_key_selector,
+        sparse_indices,
+    } = request;
+    if sparse_indices.is_some() {
+        return Err(unimplemented("sparse vector search"));
+    }
+
+    Ok(SearchRequest {
+        search_request: SearchRequestInternal {
+            vector: named_vector(vector_name, vector),
+            filter: filter.map(|f| f.try_into()).transpose()?,
+            params: params.map(|p| p.into()),
+            limit: limit as usize,
+            offset: offset.map(|o| o as usize),
+            with_payload: with_payload.map(|p| p.try_into()).transpose()?,
+            with_vector: with_vectors.map(|v| v.into()),
+            score_threshold,
+        },
+        shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+    })
+}
+
+fn recommend_request_from_proto(request: RecommendPoints) -> Result<RecommendRequest, Status> {
+    let RecommendPoints {
+        collection_name: _,
+        positive,
+        negative,
+        filter,
+        limit,
+        with_payload,
+        params,
+        score_threshold,
+        offset,
+        using,
+        with_vectors,
+        lookup_from,
+        read_consistency: _,
+        strategy,
+        positive_vectors,
+        negative_vectors,
+        timeout: _,
+        shard_key_selector,
+    } = request;
+
+    let examples = |ids: Vec<api::grpc::qdrant::PointId>,
+                    vectors: Vec<api::grpc::qdrant::Vector>|
+     -> Result<Vec<RecommendExample>, Status> {
+        let mut examples = ids
+            .into_iter()
+            .map(|id| Ok(RecommendExample::PointId(id.try_into()?)))
+            .collect::<Result<Vec<_>, Status>>()?;
+        for vector in vectors {
+            if vector.indices.is_some() {
+                return Err(unimplemented("sparse vector recommendations"));
+            }
+            examples.push(RecommendExample::Dense(vector.data));
+        }
+        Ok(examples)
+    };
+    let strategy = match strategy.map(api::grpc::qdrant::RecommendStrategy::try_from) {
+        Some(Ok(api::grpc::qdrant::RecommendStrategy::AverageVector)) => {
+            Some(RecommendStrategy::AverageVector)
+        }
+        Some(Ok(api::grpc::qdrant::RecommendStrategy::BestScore)) => {
+            Some(RecommendStrategy::BestScore)
+        }
+        Some(Err(_)) => return Err(Status::invalid_argument("Unknown recommend strategy")),
+        None => None,
+    };
+    let lookup_from = lookup_from.map(lookup_from_proto).transpose()?;
+
+    Ok(RecommendRequest {
+        recommend_request: RecommendRequestInternal {
+            positive: examples(positive, positive_vectors)?,
+            negative: examples(negative, negative_vectors)?,
+            strategy,
+            filter: filter.map(|f| f.try_into()).transpose()?,
+            params: params.map(|p| p.into()),
+            limit: limit as usize,
+            offset: offset.map(|o| o as usize),
+            with_payload: with_payload.map(|p| p.try_into()).transpose()?,
+            with_vector: with_vectors.map(|v| v.into()),
+            score_threshold,
+            using: using.map(UsingVector::Name),
+            lookup_from,
+        },
+        shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+    })
+}
+
+fn discover_request_from_proto(request: DiscoverPoints) -> Result<DiscoverRequest, Status> {
+    let DiscoverPoints {
+        collection_name: _,
+        target,
+        context,
+        filter,
+        limit,
+        with_payload,
+        params,
+        offset,
+        using,
+        with_vectors,
+        lookup_from,
+        read_consistency: _,
+        timeout: _,
+        shard_key_selector,
+    } = request;
+
+    let target = match target.and_then(|t| t.target) {
+        Some(target_vector::Target::Single(example)) => Some(example_from_proto(Some(example))?),
+        None => None,
+    };
+    let context = context
+        .into_iter()
+        .map(|pair| {
+            Ok(ContextExamplePair {
+                positive: example_from_proto(pair.positive)?,
+                negative: example_from_proto(pair.negative)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Status>>()?;
+
+    Ok(DiscoverRequest {
+        discover_request: DiscoverRequestInternal {
+            target,
+            context: (!context.is_empty()).then_some(context),
+            filter: filter.map(|f| f.try_into()).transpose()?,
+            params: params.map(|p| p.into()),
+            limit: limit as usize,
+            offset: offset.map(|o| o as usize),
+            with_payload: with_payload.map(|p| p.try_into()).transpose()?,
+            with_vector: with_vectors.map(|v| v.into()),
+            using: using.map(UsingVector::Name),
+            lookup_from: lookup_from.map(lookup_from_proto).transpose()?,
+        },
+        shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+    })
+}
+
+/// a point id or a dense vector to discover around
+fn example_from_proto(
+    example: Option<api::grpc::qdrant::VectorExample>,
+) -> Result<RecommendExample, Status> {
+    match example.and_then(|e| e.example) {
+        Some(vector_example::Example::Id(id)) => Ok(RecommendExample::PointId(id.try_into()?)),
+        Some(vector_example::Example::Vector(vector)) => {
+            if vector.indices.is_some() {
+                return Err(unimplemented("sparse vector discovery"));
+            }
+            Ok(RecommendExample::Dense(vector.data))
+        }
+        None => Err(Status::invalid_argument("Vector example is missing")),
+    }
+}
+
+fn lookup_from_proto(lookup: api::grpc::qdrant::LookupLocation) -> Result<LookupLocation, Status> {
+    Ok(LookupLocation {
+        collection: lookup.collection_name,
+        vector: lookup.vector_name,
+        shard_key: shard_key_selector_from_proto(lookup.shard_key_selector)?,
+    })
+}
+
+fn update_operation_from_proto(
+    operation: api::grpc::qdrant::PointsUpdateOperation,
+) -> Result<PointsUpdateOperation, Status> {
+    let Some(operation) = operation.operation else {
+        return Err(Status::invalid_argument("Update operation is missing"));
+    };
+    let op = match operation {
+        update_op::Operation::Upsert(update_op::PointStructList {
+            points,
+            shard_key_selector,
+        }) => PointsUpdateOperation::Upsert(PointInsertOperations::PointsList(PointsList {
+            points: points
+                .into_iter()
+                .map(PointStruct::try_from)
+                .collect::<Result<_, _>>()?,
+            shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+        })),
+        update_op::Operation::DeleteDeprecated(points) => {
+            PointsUpdateOperation::Delete(points.try_into()?)
+        }
+        update_op::Operation::DeletePoints(update_op::DeletePoints {
+            points,
+            shard_key_selector,
+        }) => PointsUpdateOperation::Delete(selector_from_proto(points, shard_key_selector)?),
+        update_op::Operation::SetPayload(set) => {
+            PointsUpdateOperation::SetPayload(set_payload_op_from_proto(set)?)
+        }
+        update_op::Operation::OverwritePayload(set) => {
+            PointsUpdateOperation::OverwritePayload(set_payload_op_from_proto(set)?)
+        }
+        update_op::Operation::DeletePayload(update_op::DeletePayload {
+            keys,
+            points_selector,
+            shard_key_selector,
+        }) => {
+            let (points, filter) = points_or_filter(points_selector)?;
+            PointsUpdateOperation::DeletePayload(DeletePayload {
+                keys,
+                points,
+                filter,
+                shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+            })
+        }
+        update_op::Operation::ClearPayloadDeprecated(points) => {
+            PointsUpdateOperation::ClearPayload(points.try_into()?)
+        }
+        update_op::Operation::ClearPayload(update_op::ClearPayload {
+            points,
+            shard_key_selector,
+        }) => PointsUpdateOperation::ClearPayload(selector_from_proto(points, shard_key_selector)?),
+        update_op::Operation::UpdateVectors(update_op::UpdateVectors {
+            points,
+            shard_key_selector,
+        }) => PointsUpdateOperation::UpdateVectors(UpdateVectors {
+            points: points_vectors_from_proto(points)?,
+            shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+        }),
+        update_op::Operation::DeleteVectors(update_op::DeleteVectors {
+            points_selector,
+            vectors,
+            shard_key_selector,
+        }) => {
+            let (points, filter) = points_or_filter(points_selector)?;
+            let Some(vectors) = vectors else {
+                return Err(Status::invalid_argument("VectorsSelector is missing"));
+            };
+            PointsUpdateOperation::DeleteVectors(DeleteVectors {
+                points,
+                filter,
+                vector: vectors.names.into_iter().collect(),
+                shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+            })
+        }
+    };
+    Ok(op)
+}
+
+/// a points selector with the shard key of its operation
+fn selector_from_proto(
+    selector: Option<api::grpc::qdrant::PointsSelector>,
+    shard_key_selector: Option<api::grpc::qdrant::ShardKeySelector>,
+) -> Result<PointsSelector, Status> {
+    let shard_key = shard_key_selector_from_proto(shard_key_selector)?;
+    let Some(selector) = selector else {
+        return Err(Status::invalid_argument("PointSelector is missing"));
+    };
+    let selector = match selector.try_into()? {
+        PointsSelector::PointIdsSelector(ids) => {
+            PointsSelector::PointIdsSelector(PointIdsList { shard_key, ..ids })
+        }
+        PointsSelector::FilterSelector(filter) => PointsSelector::FilterSelector(FilterSelector {
+            shard_key,
+            ..filter
+        }),
+    };
+    Ok(selector)
+}
+
+fn set_payload_op_from_proto(set: update_op::SetPayload) -> Result<SetPayload, Status> {
+    let update_op::SetPayload {
+        payload,
+        points_selector,
+        shard_key_selector,
+    } = set;
+    let (points, filter) = points_or_filter(points_selector)?;
+    Ok(SetPayload {
+        payload: proto_to_payloads(payload)?,
+        points,
+        filter,
+        shard_key: shard_key_selector_from_proto(shard_key_selector)?,
+    })
+}
+
+fn points_vectors_from_proto(
+    points: Vec<api::grpc::qdrant::PointVectors>,
+) -> Result<Vec<PointVectors>, Status> {
+    points
+        .into_iter()
+        .map(|point| {
+            let (Some(id), Some(vectors)) = (point.id, point.vectors) else {
+                return Err(Status::invalid_argument(
+                    "Point id and vectors are required",
+                ));
+            };
+            Ok(PointVectors {
+                id: id.try_into()?,
+                vector: vectors.try_into()?,
+            })
+        })
+        .collect()
+}
+
+fn batch_results(results: Vec<Vec<ScoredPoint>>) -> Vec<BatchResult> {
+    results
+        .into_iter()
+        .map(|points| BatchResult {
+            result: points.into_iter().map(|p| p.into()).collect(),
+        })
+        .collect()
+}
+
+fn list_aliases_response(aliases: Vec<(String, String)>, start: Instant) -> ListAliasesResponse {
+    ListAliasesResponse {
+        aliases: aliases
+            .into_iter()
+            .map(|(collection_name, alias_name)| AliasDescription {
+                alias_name,
+                collection_name,
+            })
+            .collect(),
+        time: elapsed(start),
+    }
+}
+
+fn unimplemented(what: &str) -> Status {
+    Status::unimplemented(format!("{what} is not supported by the embedded server"))
+}
+
+fn status(e: QdrantError) -> Status {
+    let code = match (&e, e.kind()) {
+        (QdrantError::StorageFull(_), _) => Code::ResourceExhausted,
+        (_, QdrantErrorKind::NotFound) => Code::NotFound,
+        (_, QdrantErrorKind::BadInput) => Code::InvalidArgument,
+        (_, QdrantErrorKind::AlreadyExists) => Code::AlreadyExists,
+        (_, QdrantErrorKind::Timeout) => Code::DeadlineExceeded,
+        (_, QdrantErrorKind::Unavailable) => Code::Unavailable,
+        (_, QdrantErrorKind::ServiceError) => Code::Internal,
+    };
+    Status::new(code, e.to_string())
+}
+
+fn elapsed(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64()
+}
@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+/// How many points are sent per upsert when importing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchSize {
+    /// always send batches of this size
+    Fixed(usize),
+    /// additive-increase/multiplicative-decrease: grow by `min` while an upsert takes at most
+    /// `target_latency`, halve when it takes longer, staying within `min..=max`
+    Adaptive {
+        initial: usize,
+        min: usize,
+        max: usize,
+        target_latency: Duration,
+    },
+}
+
+impl BatchSize {
+    /// adaptive batches between 100 and 50k points, starting from 1000
+    pub fn adaptive(target_latency: Duration) -> Self {
+        BatchSize::Adaptive {
+            initial: 1000,
+            min: 100,
+            max: 50_000,
+            target_latency,
+        }
+    }
+}
+
+impl Default for BatchSize {
+    fn default() -> Self {
+        BatchSize::Fixed(1000)
+    }
+}
+
+/// Outcome of an import.
+#[derive(Debug, Clone, Default)]
+pub struct ImportReport {
+    /// number of points upserted
+    pub imported: usize,
+    /// number of upserts sent
+    pub batches: usize,
+    /// batch size at the end of the import, where an adaptive size converged to
+    pub final_batch_size: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct BatchController {
+    size: usize,
+    policy: BatchSize,
+}
+
+impl BatchController {
+    pub(crate) fn new(policy: BatchSize) -> Self {
+        let size = match policy {
+            BatchSize::Fixed(size) => size,
+            BatchSize::Adaptive {
+                initial, min, max, ..
+            } => initial.min(max).max(min),
+        };
+        Self {
+            size: size.max(1),
+            policy,
+        }
+    }
+
+    pub(crate) fn size(&self) -> usize {
+        self.size
+    }
+
+    /// adjust the batch size after an upsert took `latency`
+    pub(crate) fn observe(&mut self, latency: Duration) {
+        if let BatchSize::Adaptive {
+            min,
+            max,
+            target_latency,
+            ..
+        } = self.policy
+        {
+            let size = if latency <= target_latency {
+                self.size.saturating_add(min)
+            } else {
+                self.size / 2
+            };
+            self.size = size.min(max).max(min).max(1);
+        }
+    }
+}
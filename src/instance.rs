@@ -1,22 +1,86 @@
 use crate::{
     helpers::{create_general_purpose_runtime, create_search_runtime, create_update_runtime},
+    stats::StatsSampler,
     AliasRequest, AliasResponse, CollectionRequest, CollectionResponse, Handler, PointsRequest,
     PointsResponse, QdrantClient, QdrantError, QdrantMsg, QueryRequest, QueryResponse, Settings,
+    SnapshotRequest, SnapshotResponse, TelemetryData,
 };
 use async_trait::async_trait;
 use collection::shards::channel_service::ChannelService;
+use config::ConfigError;
 use serde::{Deserialize, Serialize};
-use std::{mem::ManuallyDrop, sync::Arc, thread, time::Duration};
+use std::{
+    path::PathBuf,
+    sync::{mpsc as std_mpsc, Arc, RwLock},
+    thread,
+    time::{Duration, Instant},
+};
 use storage::content_manager::{
     consensus::persistent::Persistent, errors::StorageError, toc::TableOfContent,
 };
+use tempfile::TempDir;
 use tokio::{
     runtime::Handle,
-    sync::{mpsc, oneshot},
+    sync::{
+        mpsc,
+        oneshot::{self, error::TryRecvError},
+        Semaphore,
+    },
+    task::{self, JoinSet},
 };
-use tracing::{debug, warn};
+use tracing::{debug, info_span, warn, Instrument};
 
 const QDRANT_CHANNEL_BUFFER: usize = 1024;
+/// reported by the writes rejected while the storage is read-only
+const READ_ONLY_MESSAGE: &str = "storage is in read-only mode";
+
+/// Options for starting an instance.
+#[derive(Debug, Clone)]
+pub struct StartOptions {
+    /// number of requests queued for the worker before senders wait
+    pub channel_buffer: usize,
+    /// run the dispatch loop as a task on this runtime instead of a dedicated thread, see
+    /// [`QdrantInstance::start_on`]
+    ///
+    /// The search, update and general purpose runtimes of the ToC are still created and owned
+    /// by the instance, so heavy work never lands on it. Loading and dropping the ToC block,
+    /// so they run through `spawn_blocking`. Starting still waits for the load, so from a task
+    /// of this runtime, start through `spawn_blocking` too, or a current-thread runtime
+    /// deadlocks.
+    pub runtime: Option<Handle>,
+    /// settings built in code, used instead of reading the config, see
+    /// [`Settings::from_storage_config`]
+    pub settings: Option<Settings>,
+    /// store everything in a fresh temp directory, which is deleted when the client is
+    /// dropped. Meant for tests.
+    pub temp_storage: bool,
+    /// thread counts of the runtimes, overriding `storage.performance` of the settings
+    pub runtime_config: RuntimeConfig,
+    /// store collections under this directory instead of `storage.storage_path`
+    pub storage_path: Option<PathBuf>,
+}
+
+impl Default for StartOptions {
+    fn default() -> Self {
+        Self {
+            channel_buffer: QDRANT_CHANNEL_BUFFER,
+            runtime: None,
+            settings: None,
+            temp_storage: false,
+            runtime_config: RuntimeConfig::default(),
+            storage_path: None,
+        }
+    }
+}
+
+/// Thread counts of the runtimes, overriding `storage.performance` of the config.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RuntimeConfig {
+    /// threads of the search runtime, `0` to pick from the number of CPUs
+    pub max_search_threads: Option<usize>,
+    /// threads of the optimizer runtime, `0` to pick from the number of CPUs
+    pub max_optimization_threads: Option<usize>,
+}
 
 #[derive(Debug, Deserialize)]
 pub enum QdrantRequest {
@@ -24,6 +88,15 @@ pub enum QdrantRequest {
     Alias(AliasRequest),
     Points(PointsRequest),
     Query(QueryRequest),
+    Snapshot(SnapshotRequest),
+    /// round-trip through the worker, touching the ToC
+    Health,
+    /// round-trip through the worker only, answered without any storage work
+    Ping,
+    /// telemetry of the ToC at the given detail level
+    Telemetry(usize),
+    /// turn the write lock of the storage on or off
+    SetReadOnly(bool),
 }
 
 #[derive(Debug, Serialize)]
@@ -32,61 +105,362 @@ pub enum QdrantResponse {
     Alias(AliasResponse),
     Points(PointsResponse),
     Query(QueryResponse),
+    Snapshot(SnapshotResponse),
+    Health,
+    Ping,
+    Telemetry(TelemetryData),
+    /// whether the storage was read-only before
+    SetReadOnly(bool),
+}
+
+impl QdrantRequest {
+    /// short name of the operation, e.g. `points.upsert`
+    pub fn op_name(&self) -> &'static str {
+        match self {
+            QdrantRequest::Collection(req) => req.op_name(),
+            QdrantRequest::Alias(req) => req.op_name(),
+            QdrantRequest::Points(req) => req.op_name(),
+            QdrantRequest::Query(req) => req.op_name(),
+            QdrantRequest::Snapshot(req) => req.op_name(),
+            QdrantRequest::Health => "health",
+            QdrantRequest::Ping => "ping",
+            QdrantRequest::Telemetry(_) => "telemetry",
+            QdrantRequest::SetReadOnly(_) => "set_read_only",
+        }
+    }
+
+    /// whether the request changes the storage, so it's rejected while it is read-only
+    pub(crate) fn is_write(&self) -> bool {
+        match self {
+            QdrantRequest::Collection(req) => !matches!(
+                req,
+                CollectionRequest::List
+                    | CollectionRequest::ListDetailed
+                    | CollectionRequest::Get(_)
+                    | CollectionRequest::Exists(_)
+            ),
+            QdrantRequest::Alias(req) => matches!(
+                req,
+                AliasRequest::Create(_)
+                    | AliasRequest::Delete(_)
+                    | AliasRequest::Rename(_)
+                    | AliasRequest::Change(_)
+            ),
+            QdrantRequest::Points(req) => !matches!(
+                req,
+                PointsRequest::Get(_)
+                    | PointsRequest::GetWithOptions(_)
+                    | PointsRequest::GetWithShard(_)
+                    | PointsRequest::Count(_)
+                    | PointsRequest::CountWithOptions(_)
+                    | PointsRequest::Scroll(_)
+                    | PointsRequest::ScrollWithOptions(_)
+                    | PointsRequest::Sample(_)
+            ),
+            // snapshots are files next to the storage, only recovering one changes it
+            QdrantRequest::Snapshot(req) => matches!(req, SnapshotRequest::Recover(_)),
+            QdrantRequest::Query(_)
+            | QdrantRequest::Health
+            | QdrantRequest::Ping
+            | QdrantRequest::Telemetry(_)
+            | QdrantRequest::SetReadOnly(_) => false,
+        }
+    }
+
+    /// whether the request only reads, so it can be abandoned halfway without side effects
+    pub(crate) fn is_read_only(&self) -> bool {
+        matches!(
+            self,
+            QdrantRequest::Query(_)
+                | QdrantRequest::Points(
+                    PointsRequest::Get(_)
+                        | PointsRequest::GetWithOptions(_)
+                        | PointsRequest::GetWithShard(_)
+                        | PointsRequest::Count(_)
+                        | PointsRequest::CountWithOptions(_)
+                        | PointsRequest::Scroll(_)
+                        | PointsRequest::ScrollWithOptions(_)
+                        | PointsRequest::Sample(_)
+                )
+        )
+    }
+
+    /// collection the request operates on, if any
+    pub fn collection_name(&self) -> Option<&str> {
+        match self {
+            QdrantRequest::Collection(req) => req.collection_name(),
+            QdrantRequest::Alias(req) => req.collection_name(),
+            QdrantRequest::Points(req) => Some(req.collection_name()),
+            QdrantRequest::Query(req) => Some(req.collection_name()),
+            QdrantRequest::Snapshot(req) => req.collection_name(),
+            QdrantRequest::Health
+            | QdrantRequest::Ping
+            | QdrantRequest::Telemetry(_)
+            | QdrantRequest::SetReadOnly(_) => None,
+        }
+    }
 }
 
 pub struct QdrantInstance;
 
+/// The thread owning the `TableOfContent`, and the channel to talk to it.
+#[derive(Debug)]
+pub(crate) struct Worker {
+    // both are taken once the worker is closed
+    tx: Option<mpsc::Sender<QdrantMsg>>,
+    terminated_rx: Option<oneshot::Receiver<()>>,
+    handle: WorkerHandle,
+}
+
+/// Where the dispatch loop runs, see [`StartOptions::runtime`].
+#[derive(Debug)]
+enum WorkerHandle {
+    Thread(thread::JoinHandle<()>),
+    Task(task::JoinHandle<()>),
+}
+
 impl QdrantInstance {
+    /// Start an instance with the settings read from `config_path`, the files under `config/`
+    /// and the environment.
+    ///
+    /// This blocks until the collections are loaded, which can take a while for a large
+    /// storage. From async code, run it through `tokio::task::spawn_blocking` so it doesn't
+    /// stall a worker thread of the runtime.
     pub fn start(config_path: Option<String>) -> Result<Arc<QdrantClient>, QdrantError> {
-        let (tx, mut rx) = mpsc::channel::<QdrantMsg>(QDRANT_CHANNEL_BUFFER);
+        Self::start_with_options(config_path, StartOptions::default())
+    }
+
+    /// Start with the given [`StartOptions`], e.g. a larger request channel or settings built
+    /// in code, in which case `config_path` is ignored. Fails with `QdrantError::Config` if
+    /// `channel_buffer` is 0. Blocks like [`QdrantInstance::start`].
+    ///
+    /// ```ignore
+    /// let options = StartOptions {
+    ///     temp_storage: true,
+    ///     ..Default::default()
+    /// };
+    /// let client = QdrantInstance::start_with_options(None, options)?;
+    /// ```
+    pub fn start_with_options(
+        config_path: Option<String>,
+        mut options: StartOptions,
+    ) -> Result<Arc<QdrantClient>, QdrantError> {
+        if options.channel_buffer == 0 {
+            return Err(
+                ConfigError::Message("channel_buffer must be at least 1".to_string()).into(),
+            );
+        }
+        let mut settings = match options.settings.take() {
+            Some(settings) => settings,
+            None => Settings::new(config_path)?,
+        };
+        if let Some(path) = &options.storage_path {
+            settings.storage.storage_path = path.to_string_lossy().into_owned();
+        }
+        let performance = &mut settings.storage.performance;
+        if let Some(threads) = options.runtime_config.max_search_threads {
+            performance.max_search_threads = threads;
+        }
+        if let Some(threads) = options.runtime_config.max_optimization_threads {
+            performance.max_optimization_threads = threads;
+        }
+        let temp_dir = if options.temp_storage {
+            let temp_dir = TempDir::new()?;
+            let path = |name: &str| temp_dir.path().join(name).to_string_lossy().into_owned();
+            settings.storage.storage_path = path("storage");
+            settings.storage.snapshots_path = path("snapshots");
+            settings.storage.temp_path = None;
+            Some(temp_dir)
+        } else {
+            None
+        };
+        Self::launch(settings, options, temp_dir)
+    }
+
+    /// Start with the thread counts of the runtimes set in code, e.g. to cap CPU usage on a
+    /// shared machine.
+    pub fn start_with_runtime_config(
+        config_path: Option<String>,
+        runtime_config: RuntimeConfig,
+    ) -> Result<Arc<QdrantClient>, QdrantError> {
+        let options = StartOptions {
+            runtime_config,
+            ..Default::default()
+        };
+        Self::start_with_options(config_path, options)
+    }
+
+    /// Start with the dispatch loop running as a task on an existing runtime, rather than on a
+    /// dedicated thread, see [`StartOptions::runtime`].
+    ///
+    /// This blocks until the collections are loaded, so from a task of `runtime`, call it
+    /// through `spawn_blocking`.
+    pub fn start_on(runtime: Handle) -> Result<Arc<QdrantClient>, QdrantError> {
+        let options = StartOptions {
+            runtime: Some(runtime),
+            ..Default::default()
+        };
+        Self::start_with_options(None, options)
+    }
+
+    /// Start with the default settings, storing collections under `path`.
+    pub fn start_with_path(path: impl Into<PathBuf>) -> Result<Arc<QdrantClient>, QdrantError> {
+        let options = StartOptions {
+            storage_path: Some(path.into()),
+            ..Default::default()
+        };
+        Self::start_with_options(None, options)
+    }
+
+    /// Start with settings built in code, see [`Settings::from_storage_config`].
+    pub fn start_with_settings(settings: Settings) -> Result<Arc<QdrantClient>, QdrantError> {
+        let options = StartOptions {
+            settings: Some(settings),
+            ..Default::default()
+        };
+        Self::start_with_options(None, options)
+    }
+
+    /// Start a throwaway instance storing everything in a fresh temp directory, which is
+    /// deleted when the client is dropped. Meant for tests.
+    pub fn start_temp() -> Result<Arc<QdrantClient>, QdrantError> {
+        let options = StartOptions {
+            temp_storage: true,
+            ..Default::default()
+        };
+        Self::start_with_options(None, options)
+    }
+
+    fn launch(
+        settings: Settings,
+        options: StartOptions,
+        temp_dir: Option<TempDir>,
+    ) -> Result<Arc<QdrantClient>, QdrantError> {
+        if settings.max_concurrent_requests == Some(0) {
+            return Err(ConfigError::Message(
+                "max_concurrent_requests must be at least 1".to_string(),
+            )
+            .into());
+        }
+        let slow_threshold = settings.slow_query_threshold_ms.map(Duration::from_millis);
+        let stats = Arc::new(StatsSampler::new(slow_threshold));
+        let worker = Worker::spawn(settings.clone(), stats.clone(), &options)?;
+        Ok(Arc::new(QdrantClient {
+            worker: RwLock::new(worker),
+            settings,
+            options,
+            stats,
+            sessions: Default::default(),
+            vectors_configs: Default::default(),
+            payload_schemas: Default::default(),
+            max_payload_bytes: Default::default(),
+            ttl_tasks: Default::default(),
+            _temp_dir: temp_dir,
+        }))
+    }
+}
+
+impl Worker {
+    pub(crate) fn spawn(
+        settings: Settings,
+        stats: Arc<StatsSampler>,
+        options: &StartOptions,
+    ) -> Result<Self, QdrantError> {
+        let (tx, rx) = mpsc::channel::<QdrantMsg>(options.channel_buffer);
+        let limit = settings.max_concurrent_requests;
 
         let (terminated_tx, terminated_rx) = oneshot::channel::<()>();
+        // reports whether the ToC could be loaded, so startup failures reach the caller
+        let (ready_tx, ready_rx) = std_mpsc::sync_channel::<Result<(), QdrantError>>(1);
 
-        let handle = thread::Builder::new()
-            .name("qdrant".to_string())
-            .spawn(move || {
-                let (toc, rt) = start_qdrant(config_path)?;
-                let toc_clone = toc.clone();
-                rt.block_on(async move {
-                    while let Some((msg, resp_sender)) = rx.recv().await {
-                        let toc_clone = toc.clone();
-                        tokio::spawn(async move {
-                            let res = msg.handle(&toc_clone).await;
-                            if let Err(e) = resp_sender.send(res) {
-                                warn!("Failed to send response: {:?}", e);
-                            }
-                        });
+        let handle = match &options.runtime {
+            Some(runtime) => WorkerHandle::Task(runtime.spawn(async move {
+                // loading and dropping the ToC blocks, and so does dropping its runtimes
+                let started = task::spawn_blocking(move || start_qdrant(settings)).await;
+                let toc = match started {
+                    Ok(Ok((toc, _))) => {
+                        let _ = ready_tx.send(Ok(()));
+                        toc
                     }
-                    Ok::<(), QdrantError>(())
-                })?;
-
-                // clean things up
-                // see this thread: https://github.com/qdrant/qdrant/issues/1316
-                let mut toc_arc = toc_clone;
-                loop {
-                    match Arc::try_unwrap(toc_arc) {
-                        Ok(toc) => {
-                            drop(toc);
-                            if let Err(e) = terminated_tx.send(()) {
-                                warn!("Failed to send termination signal: {:?}", e);
-                            }
-                            break;
-                        }
-                        Err(toc) => {
-                            toc_arc = toc;
-                            warn!("Waiting for ToC to be gracefully dropped");
-                            thread::sleep(Duration::from_millis(300));
-                        }
+                    Ok(Err(e)) => {
+                        // the error is returned by `Worker::spawn` instead
+                        let _ = ready_tx.send(Err(e));
+                        return;
                     }
+                    Err(_) => return,
+                };
+                dispatch(rx, toc.clone(), stats, limit).await;
+                if task::spawn_blocking(move || release_toc(toc, terminated_tx))
+                    .await
+                    .is_err()
+                {
+                    warn!("Failed to release the ToC");
                 }
-                Ok::<(), QdrantError>(())
-            })
-            .unwrap();
-        Ok(Arc::new(QdrantClient {
-            tx: ManuallyDrop::new(tx),
+            })),
+            None => WorkerHandle::Thread(
+                thread::Builder::new()
+                    .name("qdrant".to_string())
+                    .spawn(move || {
+                        let (toc, rt) = match start_qdrant(settings) {
+                            Ok(started) => {
+                                let _ = ready_tx.send(Ok(()));
+                                started
+                            }
+                            Err(e) => {
+                                // the error is returned by `Worker::spawn` instead
+                                let _ = ready_tx.send(Err(e));
+                                return;
+                            }
+                        };
+                        rt.block_on(dispatch(rx, toc.clone(), stats, limit));
+                        release_toc(toc, terminated_tx);
+                    })
+                    .map_err(QdrantError::ThreadSpawn)?,
+            ),
+        };
+        match ready_rx.recv() {
+            Ok(res) => res?,
+            // the worker panicked while starting
+            Err(_) => return Err(QdrantError::WorkerUnavailable),
+        }
+        Ok(Self {
+            tx: Some(tx),
             handle,
-            terminated_rx,
-        }))
+            terminated_rx: Some(terminated_rx),
+        })
+    }
+
+    pub(crate) fn sender(&self) -> mpsc::Sender<QdrantMsg> {
+        self.tx.clone().expect("worker is closed")
+    }
+
+    /// drop the tx channel to terminate the qdrant thread, returning the receiver that is
+    /// notified once the ToC is dropped. `None` if the worker was already closed.
+    pub(crate) fn close(&mut self) -> Option<oneshot::Receiver<()>> {
+        self.tx.take();
+        self.terminated_rx.take()
+    }
+
+    /// whether the worker thread is still running
+    pub(crate) fn is_alive(&self) -> bool {
+        match &self.handle {
+            WorkerHandle::Thread(handle) => !handle.is_finished(),
+            WorkerHandle::Task(handle) => !handle.is_finished(),
+        }
+    }
+}
+
+/// Last resort if the client wasn't shut down with `QdrantClient::shutdown`: this blocks the
+/// dropping thread until the ToC is gone.
+impl Drop for Worker {
+    fn drop(&mut self) {
+        let Some(mut terminated_rx) = self.close() else {
+            return;
+        };
+        while let Err(TryRecvError::Empty) = terminated_rx.try_recv() {
+            warn!("Waiting for qdrant to terminate");
+            thread::sleep(std::time::Duration::from_millis(100));
+        }
     }
 }
 
@@ -96,6 +470,9 @@ impl Handler for QdrantRequest {
     type Error = StorageError;
 
     async fn handle(self, toc: &TableOfContent) -> Result<Self::Response, Self::Error> {
+        if self.is_write() {
+            toc.check_write_lock()?;
+        }
         match self {
             QdrantRequest::Collection(req) => {
                 let resp = req.handle(toc).await?;
@@ -113,14 +490,134 @@ impl Handler for QdrantRequest {
                 let resp = req.handle(toc).await?;
                 Ok(QdrantResponse::Query(resp))
             }
+            QdrantRequest::Snapshot(req) => {
+                let resp = req.handle(toc).await?;
+                Ok(QdrantResponse::Snapshot(resp))
+            }
+            QdrantRequest::Health => {
+                // takes the collections lock, so a wedged ToC doesn't pass
+                toc.all_collections().await;
+                Ok(QdrantResponse::Health)
+            }
+            QdrantRequest::Ping => Ok(QdrantResponse::Ping),
+            QdrantRequest::Telemetry(level) => {
+                let data = TelemetryData::collect(toc, level).await;
+                Ok(QdrantResponse::Telemetry(data))
+            }
+            QdrantRequest::SetReadOnly(enabled) => {
+                let was_read_only = toc.is_write_locked();
+                let message = enabled.then(|| READ_ONLY_MESSAGE.to_string());
+                toc.set_locks(enabled, message);
+                Ok(QdrantResponse::SetReadOnly(was_read_only))
+            }
         }
     }
 }
 
-/// Start Qdrant and get TableOfContent.
-fn start_qdrant(config_path: Option<String>) -> Result<(Arc<TableOfContent>, Handle), QdrantError> {
-    let settings = Settings::new(config_path).expect("Failed to load settings");
+/// report the request to the `metrics` recorder installed by the application
+#[cfg(feature = "metrics")]
+fn record_metrics(op: &'static str, latency: Duration, success: bool) {
+    let outcome = if success { "ok" } else { "error" };
+    metrics::histogram!("qdrant_request_duration_seconds", latency.as_secs_f64(), "op" => op);
+    metrics::counter!("qdrant_requests_total", 1, "op" => op, "outcome" => outcome);
+}
 
+/// Handle requests until all senders are gone.
+///
+/// `recv` only returns `None` once the channel is closed *and* empty, so every request
+/// queued before the close is still handled. The requests in flight are then awaited, so
+/// all of them are answered before the ToC is released.
+async fn dispatch(
+    mut rx: mpsc::Receiver<QdrantMsg>,
+    toc: Arc<TableOfContent>,
+    stats: Arc<StatsSampler>,
+    limit: Option<usize>,
+) {
+    let limiter = limit.map(|n| Arc::new(Semaphore::new(n)));
+    let mut in_flight = JoinSet::new();
+    loop {
+        let (msg, mut resp_sender) = tokio::select! {
+            msg = rx.recv() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            // reap finished requests so the set doesn't grow
+            Some(_) = in_flight.join_next(), if !in_flight.is_empty() => continue,
+        };
+        // stop receiving until a request completes, so the others wait in the channel
+        let permit = match &limiter {
+            Some(limiter) => match limiter.clone().acquire_owned().await {
+                Ok(permit) => Some(permit),
+                Err(_) => break,
+            },
+            None => None,
+        };
+        let toc = toc.clone();
+        let stats = stats.clone();
+        in_flight.spawn(async move {
+            let _permit = permit;
+            let op = msg.op_name();
+            let collection = msg.collection_name().unwrap_or_default().to_string();
+            let span = info_span!("qdrant_request", op, collection = collection.as_str());
+            let start = Instant::now();
+            let res = if msg.is_read_only() {
+                // reads are abandoned once the caller stops waiting for them
+                tokio::select! {
+                    res = msg.handle(&toc).instrument(span) => res,
+                    _ = resp_sender.closed() => {
+                        debug!("{op} cancelled by the caller");
+                        return;
+                    }
+                }
+            } else {
+                msg.handle(&toc).instrument(span).await
+            };
+            let elapsed = start.elapsed();
+            stats.record(op, elapsed);
+            if stats.is_slow(elapsed) {
+                warn!(
+                    op,
+                    collection = collection.as_str(),
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    "slow query"
+                );
+            }
+            #[cfg(feature = "metrics")]
+            record_metrics(op, elapsed, res.is_ok());
+            if let Err(e) = resp_sender.send(res) {
+                warn!("Failed to send response: {:?}", e);
+            }
+        });
+    }
+    debug!("Draining {} requests in flight", in_flight.len());
+    while in_flight.join_next().await.is_some() {}
+}
+
+/// Drop the ToC once the in-flight requests are done with it, then signal the termination.
+/// Blocks, and must not run in an async context since the ToC owns runtimes.
+fn release_toc(toc: Arc<TableOfContent>, terminated_tx: oneshot::Sender<()>) {
+    // see this thread: https://github.com/qdrant/qdrant/issues/1316
+    let mut toc_arc = toc;
+    loop {
+        match Arc::try_unwrap(toc_arc) {
+            Ok(toc) => {
+                drop(toc);
+                if let Err(e) = terminated_tx.send(()) {
+                    warn!("Failed to send termination signal: {:?}", e);
+                }
+                break;
+            }
+            Err(toc) => {
+                toc_arc = toc;
+                warn!("Waiting for ToC to be gracefully dropped");
+                thread::sleep(Duration::from_millis(300));
+            }
+        }
+    }
+}
+
+/// Start Qdrant and get TableOfContent.
+fn start_qdrant(settings: Settings) -> Result<(Arc<TableOfContent>, Handle), QdrantError> {
     memory::madvise::set_global(settings.storage.mmap_advice);
     segment::vector_storage::common::set_async_scorer(settings.storage.async_scorer);
 
@@ -135,15 +632,12 @@ fn start_qdrant(config_path: Option<String>) -> Result<(Arc<TableOfContent>, Han
 
     // Create and own search runtime out of the scope of async context to ensure correct
     // destruction of it
-    let search_runtime = create_search_runtime(settings.storage.performance.max_search_threads)
-        .expect("Can't search create runtime.");
+    let search_runtime = create_search_runtime(settings.storage.performance.max_search_threads)?;
 
     let update_runtime =
-        create_update_runtime(settings.storage.performance.max_optimization_threads)
-            .expect("Can't optimizer create runtime.");
+        create_update_runtime(settings.storage.performance.max_optimization_threads)?;
 
-    let general_runtime =
-        create_general_purpose_runtime().expect("Can't optimizer general purpose runtime.");
+    let general_runtime = create_general_purpose_runtime()?;
     let runtime_handle = general_runtime.handle().clone();
 
     // Channel service is used to manage connections between peers.
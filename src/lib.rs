@@ -1,29 +1,64 @@
+#[cfg(feature = "blocking")]
+mod blocking;
+mod builders;
 mod client;
 mod config;
 mod error;
+#[cfg(feature = "grpc")]
+mod grpc;
 mod helpers;
+mod import;
 mod instance;
 mod ops;
+#[cfg(feature = "rest")]
+mod rest;
+mod retry;
+mod schema;
+mod session;
+mod stats;
+mod stream;
+mod telemetry;
+mod ttl;
 
+use collection::operations::types::VectorsConfig;
+use instance::Worker;
+use session::SessionClock;
+use stats::StatsSampler;
 use std::backtrace::Backtrace;
-use std::mem::ManuallyDrop;
+use std::collections::HashMap;
 use std::panic;
-use std::thread::JoinHandle;
+use std::sync::{atomic::AtomicUsize, Arc, Mutex, RwLock};
 use storage::content_manager::toc::TableOfContent;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::oneshot;
 use tracing::error;
 
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingQdrantClient;
+pub use builders::*;
 pub use collection::operations::types::{
     PointRequest, PointRequestInternal, SearchRequest, SearchRequestInternal,
 };
 pub use collection::operations::{point_ops::PointStruct, types::VectorParams};
 pub use config::Settings;
-pub use error::QdrantError;
-pub use instance::QdrantInstance;
+#[allow(deprecated)]
+pub use error::ErrorKind;
+pub use error::{QdrantError, QdrantErrorKind};
+#[cfg(feature = "grpc")]
+pub use grpc::serve_grpc;
+pub use import::{BatchSize, ImportReport};
+pub use instance::{QdrantInstance, RuntimeConfig, StartOptions};
 pub use instance::{QdrantRequest, QdrantResponse};
+pub use memory::madvise::Advice;
 pub use ops::*;
+#[cfg(feature = "rest")]
+pub use rest::serve_rest;
+pub use schema::{FieldSchema, FieldType, PayloadSchema};
 pub use segment::types::{Distance, Payload, WithPayloadInterface};
+pub use session::WriteReceipt;
+pub use stats::{OpStats, StatsSnapshot};
 pub use storage::content_manager::errors::StorageError;
+pub use telemetry::{SystemTelemetry, TelemetryData};
+pub use ttl::TtlFormat;
 
 //re-exports
 pub use collection;
@@ -36,10 +71,18 @@ type QdrantResponder = oneshot::Sender<QdrantResult>;
 
 #[derive(Debug)]
 pub struct QdrantClient {
-    tx: ManuallyDrop<mpsc::Sender<QdrantMsg>>,
-    terminated_rx: oneshot::Receiver<()>,
-    #[allow(dead_code)]
-    handle: JoinHandle<Result<(), QdrantError>>,
+    worker: RwLock<Worker>,
+    settings: Settings,
+    options: StartOptions,
+    stats: Arc<StatsSampler>,
+    sessions: SessionClock,
+    vectors_configs: Mutex<HashMap<ColName, VectorsConfig>>,
+    payload_schemas: Mutex<HashMap<ColName, PayloadSchema>>,
+    // 0 means unlimited
+    max_payload_bytes: AtomicUsize,
+    ttl_tasks: Mutex<HashMap<ColName, tokio::task::JoinHandle<()>>>,
+    // declared after the worker so it's only removed once the ToC is dropped
+    _temp_dir: Option<tempfile::TempDir>,
 }
 
 #[async_trait::async_trait]
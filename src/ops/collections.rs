@@ -1,34 +1,66 @@
 use super::{shard_selector, ColName};
-use crate::{Handler, QdrantRequest};
+use crate::{Handler, QdrantRequest, WriteOptions};
 use async_trait::async_trait;
-use collection::operations::{
-    shard_key_selector::ShardKeySelector,
-    types::{AliasDescription, CollectionInfo, CollectionsAliasesResponse},
+use collection::{
+    operations::{
+        cluster_ops::CreateShardingKey,
+        config_diff::OptimizersConfigDiff,
+        point_ops::WriteOrdering,
+        shard_key_selector::ShardKeySelector,
+        shard_selector_internal::ShardSelectorInternal,
+        types::{
+            AliasDescription, CollectionInfo, CollectionStatus, CollectionsAliasesResponse,
+            UpdateResult, VectorsConfig,
+        },
+        CollectionUpdateOperations, CreateFieldIndex, CreateIndex, FieldIndexOperations,
+    },
+    shards::shard::ShardId,
 };
+use segment::types::{Distance, ShardKey};
 use serde::{Deserialize, Serialize};
+use std::{io, path::Path, time::Duration};
 use storage::content_manager::{
     collection_meta_ops::{
         AliasOperations, ChangeAliasesOperation, CollectionMetaOperations, CreateAlias,
-        CreateCollection, CreateCollectionOperation, DeleteAlias, DeleteCollectionOperation,
-        RenameAlias, UpdateCollection, UpdateCollectionOperation,
+        CreateCollection, CreateCollectionOperation, CreateShardKey, DeleteAlias,
+        DeleteCollectionOperation, DropShardKey, RenameAlias, UpdateCollection,
+        UpdateCollectionOperation,
     },
     errors::StorageError,
     toc::TableOfContent,
 };
 
+/// how often to check whether the files of a deleted collection are gone
+const DELETE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
 #[derive(Debug, Clone, Deserialize)]
 pub enum CollectionRequest {
     /// list collections
     List,
+    /// list collections along with their info
+    ListDetailed,
     /// get collection with given name
     Get(ColName),
+    /// check whether the collection exists, without computing its info
+    Exists(ColName),
     /// create collection with given info
     Create((ColName, CreateCollection)),
     /// update collection with given info
     Update((ColName, UpdateCollection)),
+    /// change the optimizer thresholds of the collection
+    UpdateOptimizers((ColName, OptimizersConfigDiff)),
     /// delete collection with given name
     Delete(ColName),
-    // CreateIndex((ColName, CreateFieldIndex)),
+    /// delete collection with given name, with [`WriteOptions`]
+    DeleteWithOptions((ColName, WriteOptions)),
+    /// create payload field index
+    CreateFieldIndex((ColName, CreateFieldIndex)),
+    /// delete payload field index with given field name
+    DeleteFieldIndex((ColName, String)),
+    /// create shards for a new shard key
+    CreateShardKey((ColName, CreateShardingKey)),
+    /// drop the shards of a shard key, with all their points
+    DropShardKey((ColName, ShardKey)),
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -43,21 +75,38 @@ pub enum AliasRequest {
     Delete(String),
     /// rename alias with old and new alias names
     Rename((String, String)),
+    /// resolve a collection or alias name to the collection name
+    Resolve(String),
+    /// apply several alias changes atomically
+    Change(Vec<AliasOperations>),
 }
 
 #[derive(Debug, Serialize)]
 pub enum CollectionResponse {
     /// list collections
     List(Vec<String>),
+    /// collections with their info
+    ListDetailed(Vec<(String, CollectionInfo)>),
     /// collection info
     Get(CollectionInfo),
+    /// existence status
+    Exists(bool),
     /// creation status
     Create(bool),
     /// update status
     Update(bool),
+    /// optimizer update status
+    UpdateOptimizers(bool),
     /// deletion status
     Delete(bool),
-    // CreateIndex(String),
+    /// field index creation status
+    CreateFieldIndex(UpdateResult),
+    /// field index deletion status
+    DeleteFieldIndex(UpdateResult),
+    /// shard key creation status
+    CreateShardKey(bool),
+    /// shard key deletion status
+    DropShardKey(bool),
 }
 
 #[derive(Debug, Serialize)]
@@ -72,6 +121,111 @@ pub enum AliasResponse {
     Delete(bool),
     /// rename status
     Rename(bool),
+    /// the collection name
+    Resolve(ColName),
+    /// change status
+    Change(bool),
+}
+
+/// A flat, serializable overview of a collection.
+///
+/// `vector_size`, `distance` and `on_disk` are only set for collections with a single unnamed
+/// vector, since named vectors may each be configured differently.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CollectionSummary {
+    pub name: ColName,
+    pub status: CollectionStatus,
+    pub points_count: usize,
+    pub indexed_count: usize,
+    pub vector_size: Option<u64>,
+    pub distance: Option<Distance>,
+    pub on_disk: Option<bool>,
+}
+
+impl CollectionSummary {
+    /// summarize the info of the collection `name`
+    pub fn new(name: impl Into<ColName>, info: &CollectionInfo) -> Self {
+        let (vector_size, distance, on_disk) = match &info.config.params.vectors {
+            VectorsConfig::Single(params) => (
+                Some(params.size.get()),
+                Some(params.distance),
+                Some(params.on_disk.unwrap_or_default()),
+            ),
+            VectorsConfig::Multi(_) => (None, None, None),
+        };
+        Self {
+            name: name.into(),
+            status: info.status,
+            points_count: info.points_count,
+            indexed_count: info.indexed_vectors_count,
+            vector_size,
+            distance,
+            on_disk,
+        }
+    }
+}
+
+impl CollectionRequest {
+    pub(crate) fn op_name(&self) -> &'static str {
+        match self {
+            CollectionRequest::List => "collection.list",
+            CollectionRequest::ListDetailed => "collection.list_detailed",
+            CollectionRequest::Get(_) => "collection.get",
+            CollectionRequest::Exists(_) => "collection.exists",
+            CollectionRequest::Create(_) => "collection.create",
+            CollectionRequest::Update(_) => "collection.update",
+            CollectionRequest::UpdateOptimizers(_) => "collection.update_optimizers",
+            CollectionRequest::Delete(_) | CollectionRequest::DeleteWithOptions(_) => {
+                "collection.delete"
+            }
+            CollectionRequest::CreateFieldIndex(_) => "collection.create_field_index",
+            CollectionRequest::DeleteFieldIndex(_) => "collection.delete_field_index",
+            CollectionRequest::CreateShardKey(_) => "collection.create_shard_key",
+            CollectionRequest::DropShardKey(_) => "collection.drop_shard_key",
+        }
+    }
+
+    pub(crate) fn collection_name(&self) -> Option<&str> {
+        match self {
+            CollectionRequest::List | CollectionRequest::ListDetailed => None,
+            CollectionRequest::Get(name)
+            | CollectionRequest::Exists(name)
+            | CollectionRequest::Delete(name)
+            | CollectionRequest::DeleteWithOptions((name, _))
+            | CollectionRequest::Create((name, _))
+            | CollectionRequest::Update((name, _))
+            | CollectionRequest::UpdateOptimizers((name, _))
+            | CollectionRequest::CreateFieldIndex((name, _))
+            | CollectionRequest::DeleteFieldIndex((name, _))
+            | CollectionRequest::CreateShardKey((name, _))
+            | CollectionRequest::DropShardKey((name, _)) => Some(name),
+        }
+    }
+}
+
+impl AliasRequest {
+    pub(crate) fn op_name(&self) -> &'static str {
+        match self {
+            AliasRequest::List => "alias.list",
+            AliasRequest::Get(_) => "alias.get",
+            AliasRequest::Create(_) => "alias.create",
+            AliasRequest::Delete(_) => "alias.delete",
+            AliasRequest::Rename(_) => "alias.rename",
+            AliasRequest::Resolve(_) => "alias.resolve",
+            AliasRequest::Change(_) => "alias.change",
+        }
+    }
+
+    pub(crate) fn collection_name(&self) -> Option<&str> {
+        match self {
+            AliasRequest::Get(name) | AliasRequest::Create((name, _)) => Some(name),
+            AliasRequest::List
+            | AliasRequest::Delete(_)
+            | AliasRequest::Rename(_)
+            | AliasRequest::Resolve(_)
+            | AliasRequest::Change(_) => None,
+        }
+    }
 }
 
 #[async_trait]
@@ -85,17 +239,51 @@ impl Handler for CollectionRequest {
                 let collections = toc.all_collections().await;
                 Ok(CollectionResponse::List(collections))
             }
+            CollectionRequest::ListDetailed => {
+                let collections = do_list_collections_detailed(toc).await?;
+                Ok(CollectionResponse::ListDetailed(collections))
+            }
             CollectionRequest::Get(name) => {
                 let collection = do_get_collection(toc, &name, None).await?;
                 Ok(CollectionResponse::Get(collection))
             }
+            CollectionRequest::Exists(name) => {
+                let exists = match toc.get_collection(&name).await {
+                    Ok(_) => true,
+                    Err(StorageError::NotFound { .. }) => false,
+                    Err(e) => return Err(e),
+                };
+                Ok(CollectionResponse::Exists(exists))
+            }
 
             CollectionRequest::Create((name, op)) => {
+                toc.check_write_lock()?;
+                // qdrant only reports an existing collection as `BadInput`, check it upfront
+                // so callers get a structured error
+                match toc.get_collection(&name).await {
+                    Ok(_) => {
+                        return Err(StorageError::AlreadyExists {
+                            description: format!("Collection `{name}` already exists!"),
+                        })
+                    }
+                    Err(StorageError::NotFound { .. }) => {}
+                    Err(e) => return Err(e),
+                }
                 let op = CollectionMetaOperations::CreateCollection(
                     CreateCollectionOperation::new(name, op),
                 );
-                toc.check_write_lock()?;
-                let ret = toc.perform_collection_meta_op(op).await?;
+                // a concurrent create may still win the race after the check
+                let ret = toc
+                    .perform_collection_meta_op(op)
+                    .await
+                    .map_err(|e| match e {
+                        StorageError::BadInput { description }
+                            if description.ends_with("already exists!") =>
+                        {
+                            StorageError::AlreadyExists { description }
+                        }
+                        e => e,
+                    })?;
 
                 Ok(CollectionResponse::Create(ret))
             }
@@ -107,13 +295,64 @@ impl Handler for CollectionRequest {
 
                 Ok(CollectionResponse::Update(ret))
             }
+            CollectionRequest::UpdateOptimizers((name, optimizers_config)) => {
+                let op = UpdateCollection {
+                    vectors: None,
+                    optimizers_config: Some(optimizers_config),
+                    params: None,
+                    hnsw_config: None,
+                    quantization_config: None,
+                };
+                let op = CollectionMetaOperations::UpdateCollection(
+                    UpdateCollectionOperation::new(name, op),
+                );
+                let ret = toc.perform_collection_meta_op(op).await?;
+
+                Ok(CollectionResponse::UpdateOptimizers(ret))
+            }
             CollectionRequest::Delete(name) => {
-                let op =
-                    CollectionMetaOperations::DeleteCollection(DeleteCollectionOperation(name));
+                CollectionRequest::DeleteWithOptions((name, Default::default()))
+                    .handle(toc)
+                    .await
+            }
+            CollectionRequest::DeleteWithOptions((name, options)) => {
+                let op = CollectionMetaOperations::DeleteCollection(DeleteCollectionOperation(
+                    name.clone(),
+                ));
                 let ret = toc.perform_collection_meta_op(op).await?;
+                if ret && options.wait {
+                    wait_collection_removed(toc, &name).await?;
+                }
 
                 Ok(CollectionResponse::Delete(ret))
             }
+            CollectionRequest::CreateFieldIndex((name, op)) => {
+                let ret =
+                    do_create_index(toc, &name, op, None, true, WriteOrdering::default()).await?;
+                Ok(CollectionResponse::CreateFieldIndex(ret))
+            }
+            CollectionRequest::DeleteFieldIndex((name, field_name)) => {
+                let ret =
+                    do_delete_index(toc, &name, field_name, None, true, WriteOrdering::default())
+                        .await?;
+                Ok(CollectionResponse::DeleteFieldIndex(ret))
+            }
+            CollectionRequest::CreateShardKey((name, op)) => {
+                let op = create_shard_key_op(toc, name, op).await?;
+                let op = CollectionMetaOperations::CreateShardKey(op);
+
+                let ret = toc.perform_collection_meta_op(op).await?;
+                Ok(CollectionResponse::CreateShardKey(ret))
+            }
+            CollectionRequest::DropShardKey((name, shard_key)) => {
+                let op = CollectionMetaOperations::DropShardKey(DropShardKey {
+                    collection_name: name,
+                    shard_key,
+                });
+
+                let ret = toc.perform_collection_meta_op(op).await?;
+                Ok(CollectionResponse::DropShardKey(ret))
+            }
         }
     }
 }
@@ -157,6 +396,18 @@ impl Handler for AliasRequest {
 
                 Ok(AliasResponse::Rename(ret))
             }
+            AliasRequest::Resolve(name) => {
+                let ret = do_resolve_alias(toc, name).await?;
+                Ok(AliasResponse::Resolve(ret))
+            }
+            AliasRequest::Change(actions) => {
+                let op =
+                    CollectionMetaOperations::ChangeAliases(ChangeAliasesOperation { actions });
+
+                let ret = toc.perform_collection_meta_op(op).await?;
+
+                Ok(AliasResponse::Change(ret))
+            }
         }
     }
 }
@@ -173,6 +424,28 @@ impl From<AliasRequest> for QdrantRequest {
     }
 }
 
+/// Every shard of the key is placed on this peer, the only one in embedded mode.
+async fn create_shard_key_op(
+    toc: &TableOfContent,
+    collection_name: String,
+    op: CreateShardingKey,
+) -> Result<CreateShardKey, StorageError> {
+    let shards_number = match op.shards_number {
+        Some(n) => n.get(),
+        None => {
+            let collection = toc.get_collection(&collection_name).await?;
+            let state = collection.state().await;
+            state.config.params.shard_number.get()
+        }
+    };
+    let placement = vec![vec![toc.this_peer_id]; shards_number as usize];
+    Ok(CreateShardKey {
+        collection_name,
+        shard_key: op.shard_key,
+        placement,
+    })
+}
+
 fn create_alias_op(collection_name: String, alias_name: String) -> ChangeAliasesOperation {
     let op = CreateAlias {
         collection_name,
@@ -216,6 +489,20 @@ async fn do_list_collection_aliases(
     Ok(CollectionsAliasesResponse { aliases })
 }
 
+async fn do_resolve_alias(toc: &TableOfContent, name: String) -> Result<String, StorageError> {
+    if toc.all_collections().await.contains(&name) {
+        return Ok(name);
+    }
+    toc.list_aliases()
+        .await?
+        .into_iter()
+        .find(|alias| alias.alias_name == name)
+        .map(|alias| alias.collection_name)
+        .ok_or_else(|| StorageError::NotFound {
+            description: format!("Collection or alias `{name}` doesn't exist!"),
+        })
+}
+
 async fn do_get_collection(
     toc: &TableOfContent,
     name: &str,
@@ -227,3 +514,129 @@ async fn do_get_collection(
 
     Ok(collection.info(&shard).await?)
 }
+
+async fn do_list_collections_detailed(
+    toc: &TableOfContent,
+) -> Result<Vec<(String, CollectionInfo)>, StorageError> {
+    let mut collections = Vec::new();
+    for name in toc.all_collections().await {
+        match do_get_collection(toc, &name, None).await {
+            Ok(info) => collections.push((name, info)),
+            // deleted since it was listed
+            Err(StorageError::NotFound { .. }) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(collections)
+}
+
+/// Qdrant moves a deleted collection into `.deleted/{name}-{uuid}` and removes the files in
+/// the background, so poll until that directory is gone.
+async fn wait_collection_removed(toc: &TableOfContent, name: &str) -> Result<(), StorageError> {
+    let deleted_path = Path::new(toc.storage_path()).join(".deleted");
+    loop {
+        let mut entries = match tokio::fs::read_dir(&deleted_path).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut pending = false;
+        while let Some(entry) = entries.next_entry().await? {
+            if is_deleted_dir_of(&entry.file_name().to_string_lossy(), name) {
+                pending = true;
+                break;
+            }
+        }
+        if !pending {
+            return Ok(());
+        }
+        tokio::time::sleep(DELETE_POLL_INTERVAL).await;
+    }
+}
+
+/// whether `file_name` is `{name}-{uuid}`, so deleting `docs` doesn't wait for `docs-v2`
+fn is_deleted_dir_of(file_name: &str, name: &str) -> bool {
+    file_name
+        .strip_prefix(name)
+        .and_then(|suffix| suffix.strip_prefix('-'))
+        .is_some_and(|id| uuid::Uuid::parse_str(id).is_ok())
+}
+
+async fn do_create_index(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: CreateFieldIndex,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let Some(field_schema) = operation.field_schema else {
+        return Err(StorageError::bad_request(
+            "Can't auto-detect field type, please specify `field_schema` in the request",
+        ));
+    };
+
+    let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: operation.field_name,
+            field_schema: Some(field_schema),
+        }),
+    );
+
+    let shard_selector = if let Some(shard_selection) = shard_selection {
+        ShardSelectorInternal::ShardId(shard_selection)
+    } else {
+        ShardSelectorInternal::All
+    };
+
+    toc.update(
+        collection_name,
+        collection_operation,
+        wait,
+        ordering,
+        shard_selector,
+    )
+    .await
+}
+
+async fn do_delete_index(
+    toc: &TableOfContent,
+    collection_name: &str,
+    field_name: String,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::DeleteIndex(field_name),
+    );
+
+    let shard_selector = if let Some(shard_selection) = shard_selection {
+        ShardSelectorInternal::ShardId(shard_selection)
+    } else {
+        ShardSelectorInternal::All
+    };
+
+    toc.update(
+        collection_name,
+        collection_operation,
+        wait,
+        ordering,
+        shard_selector,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleted_dir_matches_the_exact_collection_name() {
+        let id = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+        assert!(is_deleted_dir_of(&format!("docs-{id}"), "docs"));
+        assert!(!is_deleted_dir_of(&format!("docs-v2-{id}"), "docs"));
+        assert!(!is_deleted_dir_of("docs-v2", "docs"));
+        assert!(!is_deleted_dir_of(&format!("docs-{id}"), "doc"));
+    }
+}
@@ -1,14 +1,25 @@
 mod collections;
 mod points;
 mod query;
+mod snapshots;
 
 use collection::operations::{
-    shard_key_selector::ShardKeySelector, shard_selector_internal::ShardSelectorInternal,
+    payload_ops::{DeletePayload, SetPayload},
+    point_ops::{PointInsertOperations, PointsSelector},
+    shard_key_selector::ShardKeySelector,
+    shard_selector_internal::ShardSelectorInternal,
+    types::{
+        CountRequest, DiscoverRequest, PointRequest, RecommendGroupsRequest, RecommendRequest,
+        ScrollRequest, SearchGroupsRequest, SearchRequest,
+    },
+    vector_ops::{DeleteVectors, UpdateVectors},
 };
+use storage::content_manager::errors::StorageError;
 
 pub use collections::*;
 pub use points::*;
 pub use query::*;
+pub use snapshots::*;
 
 pub type ColName = String;
 
@@ -18,3 +29,73 @@ fn shard_selector(shard_key: Option<ShardKeySelector>) -> ShardSelectorInternal
         Some(shard_keys) => shard_keys.into(),
     }
 }
+
+/// Requests that carry their own shard key, which the read or write options may also set.
+trait ShardKeyed {
+    fn shard_key_mut(&mut self) -> &mut Option<ShardKeySelector>;
+}
+
+/// Use the shard key of the options unless the request names one itself. Naming two
+/// different keys is rejected rather than silently preferring one.
+fn merge_shard_key(
+    req: &mut impl ShardKeyed,
+    shard_key: Option<ShardKeySelector>,
+) -> Result<(), StorageError> {
+    let Some(shard_key) = shard_key else {
+        return Ok(());
+    };
+    match req.shard_key_mut() {
+        Some(existing) if *existing != shard_key => Err(StorageError::BadInput {
+            description: "Shard key of the request and of the options differ".to_string(),
+        }),
+        slot => {
+            *slot = Some(shard_key);
+            Ok(())
+        }
+    }
+}
+
+impl ShardKeyed for PointInsertOperations {
+    fn shard_key_mut(&mut self) -> &mut Option<ShardKeySelector> {
+        match self {
+            PointInsertOperations::PointsBatch(batch) => &mut batch.shard_key,
+            PointInsertOperations::PointsList(list) => &mut list.shard_key,
+        }
+    }
+}
+
+impl ShardKeyed for PointsSelector {
+    fn shard_key_mut(&mut self) -> &mut Option<ShardKeySelector> {
+        match self {
+            PointsSelector::PointIdsSelector(ids) => &mut ids.shard_key,
+            PointsSelector::FilterSelector(filter) => &mut filter.shard_key,
+        }
+    }
+}
+
+macro_rules! impl_shard_keyed {
+    ($($ty:ty),*) => {
+        $(
+            impl ShardKeyed for $ty {
+                fn shard_key_mut(&mut self) -> &mut Option<ShardKeySelector> {
+                    &mut self.shard_key
+                }
+            }
+        )*
+    };
+}
+
+impl_shard_keyed!(
+    UpdateVectors,
+    DeleteVectors,
+    SetPayload,
+    DeletePayload,
+    SearchRequest,
+    RecommendRequest,
+    SearchGroupsRequest,
+    RecommendGroupsRequest,
+    DiscoverRequest,
+    CountRequest,
+    PointRequest,
+    ScrollRequest
+);
@@ -1,5 +1,5 @@
-use super::{shard_selector, ColName};
-use crate::{Handler, QdrantRequest};
+use super::{merge_shard_key, shard_selector, ColName};
+use crate::{Handler, QdrantError, QdrantRequest, ReadOptions};
 use async_trait::async_trait;
 use collection::{
     operations::{
@@ -10,45 +10,113 @@ use collection::{
         },
         shard_key_selector::ShardKeySelector,
         shard_selector_internal::ShardSelectorInternal,
-        types::{CountRequest, CountResult, PointRequest, Record, UpdateResult},
+        types::{
+            CountRequest, CountResult, PointRequest, PointRequestInternal, Record, ScrollRequest,
+            ScrollRequestInternal, ScrollResult, UpdateResult,
+        },
         vector_ops::{DeleteVectors, UpdateVectors, UpdateVectorsOp, VectorOperations},
         CollectionUpdateOperations,
     },
     shards::shard::ShardId,
 };
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use segment::types::{
+    Condition, Filter, HasIdCondition, PointIdType, WithPayloadInterface, WithVector,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use storage::content_manager::{errors::StorageError, toc::TableOfContent};
 
+const SAMPLE_SCROLL_BATCH: usize = 1000;
+
+/// Options for write operations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WriteOptions {
+    /// wait until the change is applied before returning, instead of once it is queued
+    pub wait: bool,
+    /// how the write is coordinated between the replicas of a shard
+    ///
+    /// - `Weak` (default): applied to any available replica, no ordering guarantee
+    /// - `Medium`: routed through the replica that is currently the leader, so writes are
+    ///   ordered unless the leader changes
+    /// - `Strong`: routed through the permanent leader of the shard, writes fail if it's
+    ///   unavailable, which gives a total ordering
+    ///
+    /// With a single local replica all three behave the same.
+    pub ordering: WriteOrdering,
+    /// route the write to the shards of this key, unless the operation names one itself
+    pub shard_key: Option<ShardKeySelector>,
+}
+
 #[derive(Debug, Deserialize)]
 pub enum PointsRequest {
     /// get points with given info
     Get((ColName, PointRequest)),
+    /// get points with given info, with [`ReadOptions`]
+    GetWithOptions((ColName, PointRequest, ReadOptions)),
+    /// get points with given info, along with the shard each one was read from
+    GetWithShard((ColName, PointRequest, ReadOptions)),
     /// count points for given collection
     Count((ColName, CountRequest)),
+    /// count points for given collection, with [`ReadOptions`]
+    CountWithOptions((ColName, CountRequest, ReadOptions)),
     /// delete points with given info
     Delete((ColName, PointsSelector)),
+    /// delete points with given info, with [`WriteOptions`]
+    DeleteWithOptions((ColName, PointsSelector, WriteOptions)),
     /// upsert points with given info
     Upsert((ColName, PointInsertOperations)),
-    // update points with given info
-    // UpdateBatch((ColName, UpdateOperations)),
+    /// upsert points with given info, with [`WriteOptions`]
+    UpsertWithOptions((ColName, PointInsertOperations, WriteOptions)),
+    /// apply several updates in order
+    UpdateBatch((ColName, Vec<PointsUpdateOperation>, WriteOptions)),
     /// update point vectors
     UpdateVectors((ColName, UpdateVectors)),
+    /// update point vectors, with [`WriteOptions`]
+    UpdateVectorsWithOptions((ColName, UpdateVectors, WriteOptions)),
     /// delete point vectors
     DeleteVectors((ColName, DeleteVectors)),
+    /// delete point vectors, with [`WriteOptions`]
+    DeleteVectorsWithOptions((ColName, DeleteVectors, WriteOptions)),
     /// set point payload
     SetPayload((ColName, SetPayload)),
+    /// set point payload, with [`WriteOptions`]
+    SetPayloadWithOptions((ColName, SetPayload, WriteOptions)),
     /// overwrite point payload
     OverwritePayload((ColName, SetPayload)),
+    /// overwrite point payload, with [`WriteOptions`]
+    OverwritePayloadWithOptions((ColName, SetPayload, WriteOptions)),
     /// delete point payload
     DeletePayload((ColName, DeletePayload)),
+    /// delete point payload, with [`WriteOptions`]
+    DeletePayloadWithOptions((ColName, DeletePayload, WriteOptions)),
     /// clear point payload
     ClearPayload((ColName, PointsSelector)),
+    /// clear point payload, with [`WriteOptions`]
+    ClearPayloadWithOptions((ColName, PointsSelector, WriteOptions)),
+    /// wait until all previously acknowledged updates are applied
+    ///
+    /// Internal to [`QdrantClient::wait_for_receipt`](crate::QdrantClient::wait_for_receipt):
+    /// the barrier is an empty update, which takes an operation id and a WAL entry, so it
+    /// can't be sent as an untyped request.
+    #[doc(hidden)]
+    #[serde(skip)]
+    Sync(ColName),
+    /// get a uniform random sample of n points
+    Sample((ColName, usize)),
+    /// scroll points page by page
+    Scroll((ColName, ScrollRequest)),
+    /// scroll points page by page, with [`ReadOptions`]
+    ScrollWithOptions((ColName, ScrollRequest, ReadOptions)),
 }
 
 #[derive(Debug, Serialize)]
 pub enum PointsResponse {
     /// get points result
     Get(Vec<Record>),
+    /// get points result, with the shard of each point
+    GetWithShard(Vec<(Record, ShardId)>),
     /// count status
     Count(CountResult),
     /// delete status
@@ -67,6 +135,168 @@ pub enum PointsResponse {
     DeletePayload(UpdateResult),
     /// clear payload status
     ClearPayload(UpdateResult),
+    /// status of each update, in order
+    UpdateBatch(Vec<UpdateResult>),
+    /// sync status
+    Sync(UpdateResult),
+    /// sampled points
+    Sample(Vec<Record>),
+    /// a page of points and the offset of the next one
+    Scroll(ScrollResult),
+}
+
+/// A single update in a [`PointsRequest::UpdateBatch`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PointsUpdateOperation {
+    /// insert points or replace them by id, vectors and payload checked as for an upsert
+    Upsert(PointInsertOperations),
+    /// remove points by id or filter
+    Delete(PointsSelector),
+    /// add or change payload keys, leaving the others untouched
+    SetPayload(SetPayload),
+    /// set the payload, dropping the keys it doesn't have
+    OverwritePayload(SetPayload),
+    /// remove payload keys
+    DeletePayload(DeletePayload),
+    /// remove every payload key
+    ClearPayload(PointsSelector),
+    /// replace some named vectors of existing points
+    UpdateVectors(UpdateVectors),
+    /// remove named vectors from points
+    DeleteVectors(DeleteVectors),
+}
+
+/// Outcome of a destructive operation that supports dry runs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DryRunResult {
+    /// number of points matched by the operation
+    pub affected: usize,
+    /// result of the update, `None` for a dry run
+    pub update: Option<UpdateResult>,
+}
+
+/// Progress of a chunked upsert, which stops at the first failing chunk.
+#[derive(Debug, Default)]
+pub struct BatchUpsertReport {
+    /// number of points in the chunks that were written
+    pub succeeded: usize,
+    /// index of the chunk that failed, if any
+    pub failed_chunk_index: Option<usize>,
+    /// error of the failed chunk
+    pub error: Option<QdrantError>,
+}
+
+impl BatchUpsertReport {
+    /// whether every chunk was written
+    pub fn is_complete(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl PointsRequest {
+    pub(crate) fn op_name(&self) -> &'static str {
+        match self {
+            PointsRequest::Get(_) | PointsRequest::GetWithOptions(_) => "points.get",
+            PointsRequest::GetWithShard(_) => "points.get_with_shard",
+            PointsRequest::Count(_) | PointsRequest::CountWithOptions(_) => "points.count",
+            PointsRequest::Delete(_) | PointsRequest::DeleteWithOptions(_) => "points.delete",
+            PointsRequest::Upsert(_) | PointsRequest::UpsertWithOptions(_) => "points.upsert",
+            PointsRequest::UpdateVectors(_) | PointsRequest::UpdateVectorsWithOptions(_) => {
+                "points.update_vectors"
+            }
+            PointsRequest::DeleteVectors(_) | PointsRequest::DeleteVectorsWithOptions(_) => {
+                "points.delete_vectors"
+            }
+            PointsRequest::SetPayload(_) | PointsRequest::SetPayloadWithOptions(_) => {
+                "points.set_payload"
+            }
+            PointsRequest::OverwritePayload(_) | PointsRequest::OverwritePayloadWithOptions(_) => {
+                "points.overwrite_payload"
+            }
+            PointsRequest::DeletePayload(_) | PointsRequest::DeletePayloadWithOptions(_) => {
+                "points.delete_payload"
+            }
+            PointsRequest::ClearPayload(_) | PointsRequest::ClearPayloadWithOptions(_) => {
+                "points.clear_payload"
+            }
+            PointsRequest::UpdateBatch(_) => "points.update_batch",
+            PointsRequest::Sync(_) => "points.sync",
+            PointsRequest::Sample(_) => "points.sample",
+            PointsRequest::Scroll(_) | PointsRequest::ScrollWithOptions(_) => "points.scroll",
+        }
+    }
+
+    pub(crate) fn collection_name(&self) -> &str {
+        match self {
+            PointsRequest::Get((name, _))
+            | PointsRequest::GetWithOptions((name, _, _))
+            | PointsRequest::GetWithShard((name, _, _))
+            | PointsRequest::Sample((name, _))
+            | PointsRequest::Scroll((name, _))
+            | PointsRequest::ScrollWithOptions((name, _, _))
+            | PointsRequest::Count((name, _))
+            | PointsRequest::CountWithOptions((name, _, _))
+            | PointsRequest::Delete((name, _))
+            | PointsRequest::DeleteWithOptions((name, _, _))
+            | PointsRequest::Upsert((name, _))
+            | PointsRequest::UpsertWithOptions((name, _, _))
+            | PointsRequest::UpdateVectors((name, _))
+            | PointsRequest::UpdateVectorsWithOptions((name, _, _))
+            | PointsRequest::DeleteVectors((name, _))
+            | PointsRequest::DeleteVectorsWithOptions((name, _, _))
+            | PointsRequest::SetPayload((name, _))
+            | PointsRequest::SetPayloadWithOptions((name, _, _))
+            | PointsRequest::OverwritePayload((name, _))
+            | PointsRequest::OverwritePayloadWithOptions((name, _, _))
+            | PointsRequest::DeletePayload((name, _))
+            | PointsRequest::DeletePayloadWithOptions((name, _, _))
+            | PointsRequest::ClearPayload((name, _))
+            | PointsRequest::ClearPayloadWithOptions((name, _, _))
+            | PointsRequest::UpdateBatch((name, _, _)) => name,
+            PointsRequest::Sync(name) => name,
+        }
+    }
+
+    /// the `*WithOptions` counterpart of a variant without options, with the default
+    /// options
+    fn with_default_options(self) -> Self {
+        match self {
+            PointsRequest::Delete((name, req)) => {
+                PointsRequest::DeleteWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::Upsert((name, req)) => {
+                PointsRequest::UpsertWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::UpdateVectors((name, req)) => {
+                PointsRequest::UpdateVectorsWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::DeleteVectors((name, req)) => {
+                PointsRequest::DeleteVectorsWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::SetPayload((name, req)) => {
+                PointsRequest::SetPayloadWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::OverwritePayload((name, req)) => {
+                PointsRequest::OverwritePayloadWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::DeletePayload((name, req)) => {
+                PointsRequest::DeletePayloadWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::ClearPayload((name, req)) => {
+                PointsRequest::ClearPayloadWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::Count((name, req)) => {
+                PointsRequest::CountWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::Get((name, req)) => {
+                PointsRequest::GetWithOptions((name, req, Default::default()))
+            }
+            PointsRequest::Scroll((name, req)) => {
+                PointsRequest::ScrollWithOptions((name, req, Default::default()))
+            }
+            req => req,
+        }
+    }
 }
 
 #[async_trait]
@@ -76,116 +306,225 @@ impl Handler for PointsRequest {
 
     async fn handle(self, toc: &TableOfContent) -> Result<Self::Response, Self::Error> {
         match self {
-            PointsRequest::Get((col_name, request)) => {
+            // the variants without options run as their `*WithOptions` counterpart
+            req @ (PointsRequest::Get(_)
+            | PointsRequest::Scroll(_)
+            | PointsRequest::Count(_)
+            | PointsRequest::Delete(_)
+            | PointsRequest::Upsert(_)
+            | PointsRequest::UpdateVectors(_)
+            | PointsRequest::DeleteVectors(_)
+            | PointsRequest::SetPayload(_)
+            | PointsRequest::OverwritePayload(_)
+            | PointsRequest::DeletePayload(_)
+            | PointsRequest::ClearPayload(_)) => req.with_default_options().handle(toc).await,
+            PointsRequest::GetWithOptions((col_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
                 let PointRequest {
                     point_request,
                     shard_key,
                 } = request;
 
                 let shard = shard_selector(shard_key);
-                let ret = toc.retrieve(&col_name, point_request, None, shard).await?;
+                let ret = toc
+                    .retrieve(&col_name, point_request, options.consistency, shard)
+                    .await?;
                 Ok(PointsResponse::Get(ret))
             }
-            PointsRequest::Count((col_name, request)) => {
+            PointsRequest::GetWithShard((col_name, request, options)) => {
+                let ret = do_get_points_with_shard(toc, &col_name, request, options).await?;
+                Ok(PointsResponse::GetWithShard(ret))
+            }
+            PointsRequest::CountWithOptions((col_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
                 let CountRequest {
                     count_request,
                     shard_key,
                 } = request;
 
                 let shard = shard_selector(shard_key);
-                let ret = toc.count(&col_name, count_request, None, shard).await?;
+                let ret = toc
+                    .count(&col_name, count_request, options.consistency, shard)
+                    .await?;
                 Ok(PointsResponse::Count(ret))
             }
-            PointsRequest::Delete((col_name, selector)) => {
+            PointsRequest::DeleteWithOptions((col_name, mut selector, options)) => {
+                merge_shard_key(&mut selector, options.shard_key)?;
                 let ret = do_delete_points(
                     toc,
                     &col_name,
                     selector,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::Delete(ret))
             }
-            PointsRequest::Upsert((col_name, ops)) => {
+            PointsRequest::UpsertWithOptions((col_name, mut ops, options)) => {
+                merge_shard_key(&mut ops, options.shard_key)?;
                 let ret =
-                    do_upsert_points(toc, &col_name, ops, None, false, WriteOrdering::default())
+                    do_upsert_points(toc, &col_name, ops, None, options.wait, options.ordering)
                         .await?;
                 Ok(PointsResponse::Upsert(ret))
             }
-            PointsRequest::UpdateVectors((col_name, operations)) => {
+            PointsRequest::UpdateVectorsWithOptions((col_name, mut operations, options)) => {
+                merge_shard_key(&mut operations, options.shard_key)?;
                 let ret = do_update_vectors(
                     toc,
                     &col_name,
                     operations,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::UpdateVectors(ret))
             }
-            PointsRequest::DeleteVectors((col_name, operations)) => {
+            PointsRequest::DeleteVectorsWithOptions((col_name, mut operations, options)) => {
+                merge_shard_key(&mut operations, options.shard_key)?;
                 let ret = do_delete_vectors(
                     toc,
                     &col_name,
                     operations,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::DeleteVectors(ret))
             }
-            PointsRequest::SetPayload((col_name, payload)) => {
+            PointsRequest::SetPayloadWithOptions((col_name, mut payload, options)) => {
+                merge_shard_key(&mut payload, options.shard_key)?;
                 let ret = do_set_payload(
                     toc,
                     &col_name,
                     payload,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::SetPayload(ret))
             }
-            PointsRequest::OverwritePayload((col_name, payload)) => {
+            PointsRequest::OverwritePayloadWithOptions((col_name, mut payload, options)) => {
+                merge_shard_key(&mut payload, options.shard_key)?;
                 let ret = do_overwrite_payload(
                     toc,
                     &col_name,
                     payload,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::OverwritePayload(ret))
             }
-            PointsRequest::DeletePayload((col_name, payload)) => {
+            PointsRequest::DeletePayloadWithOptions((col_name, mut payload, options)) => {
+                merge_shard_key(&mut payload, options.shard_key)?;
                 let ret = do_delete_payload(
                     toc,
                     &col_name,
                     payload,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::DeletePayload(ret))
             }
-            PointsRequest::ClearPayload((col_name, selector)) => {
+            PointsRequest::ClearPayloadWithOptions((col_name, mut selector, options)) => {
+                merge_shard_key(&mut selector, options.shard_key)?;
                 let ret = do_clear_payload(
                     toc,
                     &col_name,
                     selector,
                     None,
-                    false,
-                    WriteOrdering::default(),
+                    options.wait,
+                    options.ordering,
                 )
                 .await?;
                 Ok(PointsResponse::ClearPayload(ret))
             }
+            PointsRequest::UpdateBatch((col_name, operations, options)) => {
+                let mut ret = Vec::with_capacity(operations.len());
+                for op in operations {
+                    let req = op.into_request(col_name.clone(), options.clone());
+                    match req.handle(toc).await? {
+                        PointsResponse::Upsert(res)
+                        | PointsResponse::Delete(res)
+                        | PointsResponse::SetPayload(res)
+                        | PointsResponse::OverwritePayload(res)
+                        | PointsResponse::DeletePayload(res)
+                        | PointsResponse::ClearPayload(res)
+                        | PointsResponse::UpdateVectors(res)
+                        | PointsResponse::DeleteVectors(res) => ret.push(res),
+                        res => unreachable!("Unexpected response: {:?}", res),
+                    }
+                }
+                Ok(PointsResponse::UpdateBatch(ret))
+            }
+            PointsRequest::Sync(col_name) => {
+                let ret = do_sync_points(toc, &col_name).await?;
+                Ok(PointsResponse::Sync(ret))
+            }
+            PointsRequest::Sample((col_name, n)) => {
+                let ret = do_sample_points(toc, &col_name, n).await?;
+                Ok(PointsResponse::Sample(ret))
+            }
+            PointsRequest::ScrollWithOptions((col_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
+                let ScrollRequest {
+                    scroll_request,
+                    shard_key,
+                } = request;
+
+                let shard = shard_selector(shard_key);
+                let scroll = toc.scroll(&col_name, scroll_request, options.consistency, shard);
+                // scroll has no timeout of its own
+                let ret = match options.timeout {
+                    Some(timeout) => {
+                        tokio::time::timeout(timeout, scroll).await.map_err(|_| {
+                            StorageError::Timeout {
+                                description: format!("Scroll didn't finish in {timeout:?}"),
+                            }
+                        })??
+                    }
+                    None => scroll.await?,
+                };
+                Ok(PointsResponse::Scroll(ret))
+            }
+        }
+    }
+}
+
+impl PointsUpdateOperation {
+    fn into_request(self, col_name: ColName, options: WriteOptions) -> PointsRequest {
+        match self {
+            PointsUpdateOperation::Upsert(op) => {
+                PointsRequest::UpsertWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::Delete(op) => {
+                PointsRequest::DeleteWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::SetPayload(op) => {
+                PointsRequest::SetPayloadWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::OverwritePayload(op) => {
+                PointsRequest::OverwritePayloadWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::DeletePayload(op) => {
+                PointsRequest::DeletePayloadWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::ClearPayload(op) => {
+                PointsRequest::ClearPayloadWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::UpdateVectors(op) => {
+                PointsRequest::UpdateVectorsWithOptions((col_name, op, options))
+            }
+            PointsUpdateOperation::DeleteVectors(op) => {
+                PointsRequest::DeleteVectorsWithOptions((col_name, op, options))
+            }
         }
     }
 }
@@ -208,7 +547,7 @@ async fn do_upsert_points(
     let collection_operation =
         CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(operation));
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -237,7 +576,7 @@ async fn do_delete_points(
         }
     };
     let collection_operation = CollectionUpdateOperations::PointOperation(point_operation);
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -263,7 +602,7 @@ async fn do_update_vectors(
         VectorOperations::UpdateVectors(UpdateVectorsOp { points }),
     );
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -294,7 +633,7 @@ async fn do_delete_vectors(
 
     let mut result = None;
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     if let Some(filter) = filter {
         let vectors_operation =
@@ -352,7 +691,7 @@ async fn do_set_payload(
             filter,
         }));
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -386,7 +725,7 @@ async fn do_overwrite_payload(
             filter,
         }));
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -420,7 +759,7 @@ async fn do_delete_payload(
             filter,
         }));
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -451,7 +790,7 @@ async fn do_clear_payload(
 
     let collection_operation = CollectionUpdateOperations::PayloadOperation(point_operation);
 
-    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key);
+    let shard_selector = get_shard_selector_for_update(shard_selection, shard_key)?;
 
     toc.update(
         collection_name,
@@ -463,6 +802,122 @@ async fn do_clear_payload(
     .await
 }
 
+/// Sends a no-op update through the collection's update queue and waits for it.
+///
+/// Updates are applied in order, so once this returns every update acknowledged
+/// before it is visible to reads. The filter matches no point, so no data changes, but the
+/// update still takes an operation id and a WAL entry; callers skip it when they can.
+async fn do_sync_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<UpdateResult, StorageError> {
+    let filter = Filter::new_must(Condition::HasId(HasIdCondition {
+        has_id: HashSet::new(),
+    }));
+    let collection_operation =
+        CollectionUpdateOperations::PointOperation(PointOperations::DeletePointsByFilter(filter));
+
+    let shard_selector = get_shard_selector_for_update(None, None)?;
+
+    toc.update(
+        collection_name,
+        collection_operation,
+        true,
+        WriteOrdering::default(),
+        shard_selector,
+    )
+    .await
+}
+
+/// Retrieves the points along with the id of the shard each one was read from.
+///
+/// `toc.retrieve` doesn't tell which shard a point came from, so ask every shard in turn.
+async fn do_get_points_with_shard(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: PointRequest,
+    options: ReadOptions,
+) -> Result<Vec<(Record, ShardId)>, StorageError> {
+    if request.shard_key.is_some() || options.shard_key.is_some() {
+        return Err(StorageError::bad_request(
+            "Shard key is not supported when reading points along with their shard",
+        ));
+    }
+    let collection = toc.get_collection(collection_name).await?;
+    let mut shard_ids: Vec<_> = collection.state().await.shards.into_keys().collect();
+    shard_ids.sort_unstable();
+
+    let mut records = Vec::new();
+    for shard_id in shard_ids {
+        let shard = ShardSelectorInternal::ShardId(shard_id);
+        let ret = toc
+            .retrieve(
+                collection_name,
+                request.point_request.clone(),
+                options.consistency,
+                shard,
+            )
+            .await?;
+        records.extend(ret.into_iter().map(|record| (record, shard_id)));
+    }
+    Ok(records)
+}
+
+/// Picks n point ids with reservoir sampling over a full scroll of the collection, then
+/// retrieves them with their payload.
+///
+/// The engine has no random sampling query, so the sample is uniform but the cost grows
+/// with the size of the collection.
+async fn do_sample_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    n: usize,
+) -> Result<Vec<Record>, StorageError> {
+    if n == 0 {
+        return Ok(vec![]);
+    }
+
+    let mut rng = StdRng::from_entropy();
+    let mut reservoir: Vec<PointIdType> = Vec::with_capacity(n);
+    let mut seen = 0;
+    let mut offset = None;
+    loop {
+        let request = ScrollRequestInternal {
+            offset,
+            limit: Some(SAMPLE_SCROLL_BATCH),
+            filter: None,
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: WithVector::Bool(false),
+        };
+        let res = toc
+            .scroll(collection_name, request, None, ShardSelectorInternal::All)
+            .await?;
+        for record in res.points {
+            if reservoir.len() < n {
+                reservoir.push(record.id);
+            } else {
+                let i = rng.gen_range(0..=seen);
+                if i < n {
+                    reservoir[i] = record.id;
+                }
+            }
+            seen += 1;
+        }
+        match res.next_page_offset {
+            Some(next) => offset = Some(next),
+            None => break,
+        }
+    }
+
+    let request = PointRequestInternal {
+        ids: reservoir,
+        with_payload: Some(WithPayloadInterface::Bool(true)),
+        with_vector: WithVector::Bool(false),
+    };
+    toc.retrieve(collection_name, request, None, ShardSelectorInternal::All)
+        .await
+}
+
 /// Converts a pair of parameters into a shard selector
 /// suitable for update operations.
 ///
@@ -471,25 +926,49 @@ async fn do_clear_payload(
 /// while empty shard selector in case of search means all shards.
 ///
 /// Parameters:
-/// - shard_selection: selection of the exact shard ID, always have priority over shard_key
+/// - shard_selection: selection of the exact shard ID, mutually exclusive with shard_key
 /// - shard_key: selection of the shard key, can be a single key or a list of keys
 ///
 /// Returns:
 /// - ShardSelectorInternal - resolved shard selector
+/// - StorageError::BadInput - if both a shard ID and a shard key are given
 fn get_shard_selector_for_update(
     shard_selection: Option<ShardId>,
     shard_key: Option<ShardKeySelector>,
-) -> ShardSelectorInternal {
+) -> Result<ShardSelectorInternal, StorageError> {
     match (shard_selection, shard_key) {
-        (Some(shard_selection), None) => ShardSelectorInternal::ShardId(shard_selection),
-        (Some(shard_selection), Some(_)) => {
-            debug_assert!(
-                false,
-                "Shard selection and shard key are mutually exclusive"
-            );
-            ShardSelectorInternal::ShardId(shard_selection)
-        }
-        (None, Some(shard_key)) => ShardSelectorInternal::from(shard_key),
-        (None, None) => ShardSelectorInternal::Empty,
+        (Some(shard_selection), None) => Ok(ShardSelectorInternal::ShardId(shard_selection)),
+        (Some(_), Some(_)) => Err(StorageError::BadInput {
+            description: "Shard selection and shard key are mutually exclusive".to_string(),
+        }),
+        (None, Some(shard_key)) => Ok(ShardSelectorInternal::from(shard_key)),
+        (None, None) => Ok(ShardSelectorInternal::Empty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QdrantErrorKind;
+    use segment::types::ShardKey;
+
+    #[test]
+    fn update_with_shard_id_and_shard_key_is_bad_input() {
+        let shard_key = ShardKeySelector::ShardKey(ShardKey::Number(1));
+        let err = get_shard_selector_for_update(Some(0), Some(shard_key)).unwrap_err();
+        assert_eq!(QdrantError::from(err).kind(), QdrantErrorKind::BadInput);
+    }
+
+    #[test]
+    fn update_with_shard_id_or_shard_key_is_routed() {
+        let shard_key = ShardKeySelector::ShardKey(ShardKey::Number(1));
+        assert!(matches!(
+            get_shard_selector_for_update(Some(0), None),
+            Ok(ShardSelectorInternal::ShardId(0))
+        ));
+        assert!(matches!(
+            get_shard_selector_for_update(None, Some(shard_key)),
+            Ok(ShardSelectorInternal::ShardKey(ShardKey::Number(1)))
+        ));
     }
 }
@@ -1,38 +1,170 @@
-use std::time::Duration;
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
 
-use super::{shard_selector, ColName};
+use super::{merge_shard_key, shard_selector, ColName};
 use crate::{Handler, QdrantRequest};
 use async_trait::async_trait;
 use collection::{
     common::batching::batch_requests,
     operations::{
         consistency_params::ReadConsistency,
+        shard_key_selector::ShardKeySelector,
         shard_selector_internal::ShardSelectorInternal,
         types::{
-            CoreSearchRequest, CoreSearchRequestBatch, GroupsResult, RecommendGroupsRequest,
-            RecommendGroupsRequestInternal, RecommendRequest, RecommendRequestBatch,
+            CoreSearchRequest, CoreSearchRequestBatch, DiscoverRequest, DiscoverRequestBatch,
+            GroupsResult, RecommendGroupsRequest, RecommendGroupsRequestInternal, RecommendRequest,
+            RecommendRequestBatch, RecommendRequestInternal, ScrollRequestInternal,
             SearchGroupsRequest, SearchGroupsRequestInternal, SearchRequest, SearchRequestBatch,
+            SearchRequestInternal,
         },
     },
 };
-use segment::types::ScoredPoint;
+use segment::{
+    data_types::vectors::{NamedVector, NamedVectorStruct, Vector, VectorStruct},
+    types::{
+        Condition, Filter, HasIdCondition, PointIdType, ScoreType, ScoredPoint,
+        WithPayloadInterface, WithVector,
+    },
+};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use storage::content_manager::{errors::StorageError, toc::TableOfContent};
+use tokio_util::sync::CancellationToken;
+
+const FACET_SCROLL_BATCH: usize = 1000;
+
+/// Options for read operations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadOptions {
+    /// only read the shards of this key, unless the request names one itself
+    pub shard_key: Option<ShardKeySelector>,
+    /// how many replicas of a shard have to answer, `None` for any single one
+    pub consistency: Option<ReadConsistency>,
+    /// give up after this long with `QdrantError::Timeout`
+    pub timeout: Option<Duration>,
+    /// give up with `QdrantError::Cancelled` once the token is cancelled. Only honored by
+    /// search, recommend, get and count, and never sent to the worker
+    #[serde(skip)]
+    pub cancel: Option<CancellationToken>,
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum QueryRequest {
     /// search for vectors
     Search((ColName, SearchRequest)),
+    /// search for vectors, with [`ReadOptions`]
+    SearchWithOptions((ColName, SearchRequest, ReadOptions)),
     /// search for vectors in batch
     SearchBatch((ColName, SearchRequestBatch)),
+    /// search for vectors in batch, with [`ReadOptions`]
+    SearchBatchWithOptions((ColName, SearchRequestBatch, ReadOptions)),
     /// search group by
     SearchGroup((ColName, SearchGroupsRequest)),
+    /// search group by, with [`ReadOptions`]
+    SearchGroupWithOptions((ColName, SearchGroupsRequest, ReadOptions)),
     /// recommend points
     Recommend((ColName, RecommendRequest)),
+    /// recommend points, with [`ReadOptions`]
+    RecommendWithOptions((ColName, RecommendRequest, ReadOptions)),
     /// recommend points in batch
     RecommendBatch((ColName, RecommendRequestBatch)),
+    /// recommend points in batch, with [`ReadOptions`]
+    RecommendBatchWithOptions((ColName, RecommendRequestBatch, ReadOptions)),
     /// recommend group by
     RecommendGroup((ColName, RecommendGroupsRequest)),
+    /// recommend group by, with [`ReadOptions`]
+    RecommendGroupWithOptions((ColName, RecommendGroupsRequest, ReadOptions)),
+    /// discover points with a target and context pairs
+    Discover((ColName, DiscoverRequest)),
+    /// discover points with a target and context pairs, with [`ReadOptions`]
+    DiscoverWithOptions((ColName, DiscoverRequest, ReadOptions)),
+    /// discover points in batch
+    DiscoverBatch((ColName, DiscoverRequestBatch)),
+    /// discover points in batch, with [`ReadOptions`]
+    DiscoverBatchWithOptions((ColName, DiscoverRequestBatch, ReadOptions)),
+    /// count the distinct values of a payload field
+    Facet((ColName, FacetRequest, ReadOptions)),
+    /// nearest neighbours of each point of a sample, among that sample
+    SearchMatrix((ColName, SearchMatrixRequest, ReadOptions)),
+}
+
+/// Pairwise distances within a sample of the points matching `filter`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatrixRequest {
+    pub filter: Option<Filter>,
+    /// number of points in the sample
+    pub sample: usize,
+    /// number of neighbours kept per point
+    pub limit: usize,
+    /// named vector to compare, the default one if `None`
+    pub using: Option<String>,
+}
+
+/// Sampled point ids, and the neighbours found for each of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchMatrixResult {
+    pub ids: Vec<PointIdType>,
+    pub pairs: Vec<SearchMatrixPair>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchMatrixPair {
+    pub a: PointIdType,
+    pub b: PointIdType,
+    pub score: ScoreType,
+}
+
+/// Sparse matrix of the pairs: row and column offsets index into `ids`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchMatrixOffsets {
+    pub offsets_row: Vec<u64>,
+    pub offsets_col: Vec<u64>,
+    pub scores: Vec<ScoreType>,
+    pub ids: Vec<PointIdType>,
+}
+
+impl From<SearchMatrixResult> for SearchMatrixOffsets {
+    fn from(res: SearchMatrixResult) -> Self {
+        let index: HashMap<_, _> = res
+            .ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| (*id, i as u64))
+            .collect();
+        let mut offsets = SearchMatrixOffsets {
+            offsets_row: Vec::with_capacity(res.pairs.len()),
+            offsets_col: Vec::with_capacity(res.pairs.len()),
+            scores: Vec::with_capacity(res.pairs.len()),
+            ids: Vec::new(),
+        };
+        for pair in res.pairs {
+            offsets.offsets_row.push(index[&pair.a]);
+            offsets.offsets_col.push(index[&pair.b]);
+            offsets.scores.push(pair.score);
+        }
+        offsets.ids = res.ids;
+        offsets
+    }
+}
+
+/// Count the distinct values of the payload field `key` among the points matching `filter`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FacetRequest {
+    /// top-level payload field
+    pub key: String,
+    pub filter: Option<Filter>,
+    /// number of most frequent values to return
+    pub limit: usize,
+}
+
+/// A value of the faceted field and the number of points having it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FacetHit {
+    pub value: Value,
+    pub count: usize,
 }
 
 #[derive(Debug, Serialize)]
@@ -49,6 +181,96 @@ pub enum QueryResponse {
     RecommendBatch(Vec<Vec<ScoredPoint>>),
     /// recommend group by result
     RecommendGroup(GroupsResult),
+    /// discover result
+    Discover(Vec<ScoredPoint>),
+    /// discover result in batch
+    DiscoverBatch(Vec<Vec<ScoredPoint>>),
+    /// facet counts, most frequent first
+    Facet(Vec<FacetHit>),
+    /// search matrix result
+    SearchMatrix(SearchMatrixResult),
+}
+
+impl QueryRequest {
+    pub(crate) fn op_name(&self) -> &'static str {
+        match self {
+            QueryRequest::Search(_) | QueryRequest::SearchWithOptions(_) => "query.search",
+            QueryRequest::SearchBatch(_) | QueryRequest::SearchBatchWithOptions(_) => {
+                "query.search_batch"
+            }
+            QueryRequest::SearchGroup(_) | QueryRequest::SearchGroupWithOptions(_) => {
+                "query.search_group"
+            }
+            QueryRequest::Recommend(_) | QueryRequest::RecommendWithOptions(_) => "query.recommend",
+            QueryRequest::RecommendBatch(_) | QueryRequest::RecommendBatchWithOptions(_) => {
+                "query.recommend_batch"
+            }
+            QueryRequest::RecommendGroup(_) | QueryRequest::RecommendGroupWithOptions(_) => {
+                "query.recommend_group"
+            }
+            QueryRequest::Discover(_) | QueryRequest::DiscoverWithOptions(_) => "query.discover",
+            QueryRequest::DiscoverBatch(_) | QueryRequest::DiscoverBatchWithOptions(_) => {
+                "query.discover_batch"
+            }
+            QueryRequest::Facet(_) => "query.facet",
+            QueryRequest::SearchMatrix(_) => "query.search_matrix",
+        }
+    }
+
+    pub(crate) fn collection_name(&self) -> &str {
+        match self {
+            QueryRequest::Search((name, _))
+            | QueryRequest::SearchWithOptions((name, _, _))
+            | QueryRequest::SearchBatch((name, _))
+            | QueryRequest::SearchBatchWithOptions((name, _, _))
+            | QueryRequest::SearchGroup((name, _))
+            | QueryRequest::SearchGroupWithOptions((name, _, _))
+            | QueryRequest::Recommend((name, _))
+            | QueryRequest::RecommendWithOptions((name, _, _))
+            | QueryRequest::RecommendBatch((name, _))
+            | QueryRequest::RecommendBatchWithOptions((name, _, _))
+            | QueryRequest::RecommendGroup((name, _))
+            | QueryRequest::RecommendGroupWithOptions((name, _, _))
+            | QueryRequest::Discover((name, _))
+            | QueryRequest::DiscoverWithOptions((name, _, _))
+            | QueryRequest::DiscoverBatch((name, _))
+            | QueryRequest::DiscoverBatchWithOptions((name, _, _))
+            | QueryRequest::Facet((name, _, _))
+            | QueryRequest::SearchMatrix((name, _, _)) => name,
+        }
+    }
+
+    /// the `*WithOptions` counterpart of a variant without options, with the default
+    /// options
+    fn with_default_options(self) -> Self {
+        match self {
+            QueryRequest::Search((name, req)) => {
+                QueryRequest::SearchWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::SearchBatch((name, req)) => {
+                QueryRequest::SearchBatchWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::SearchGroup((name, req)) => {
+                QueryRequest::SearchGroupWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::Recommend((name, req)) => {
+                QueryRequest::RecommendWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::RecommendBatch((name, req)) => {
+                QueryRequest::RecommendBatchWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::RecommendGroup((name, req)) => {
+                QueryRequest::RecommendGroupWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::Discover((name, req)) => {
+                QueryRequest::DiscoverWithOptions((name, req, Default::default()))
+            }
+            QueryRequest::DiscoverBatch((name, req)) => {
+                QueryRequest::DiscoverBatchWithOptions((name, req, Default::default()))
+            }
+            req => req,
+        }
+    }
 }
 
 #[async_trait]
@@ -58,7 +280,17 @@ impl Handler for QueryRequest {
 
     async fn handle(self, toc: &TableOfContent) -> Result<Self::Response, Self::Error> {
         match self {
-            QueryRequest::Search((collection_name, request)) => {
+            // the variants without options run as their `*WithOptions` counterpart
+            req @ (QueryRequest::Recommend(_)
+            | QueryRequest::RecommendBatch(_)
+            | QueryRequest::RecommendGroup(_)
+            | QueryRequest::Discover(_)
+            | QueryRequest::DiscoverBatch(_)
+            | QueryRequest::Search(_)
+            | QueryRequest::SearchBatch(_)
+            | QueryRequest::SearchGroup(_)) => req.with_default_options().handle(toc).await,
+            QueryRequest::SearchWithOptions((collection_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
                 let SearchRequest {
                     search_request,
                     shard_key,
@@ -69,18 +301,23 @@ impl Handler for QueryRequest {
                     toc,
                     &collection_name,
                     search_request.into(),
-                    None,
+                    options.consistency,
                     shard,
-                    None,
+                    options.timeout,
                 )
                 .await?;
                 Ok(QueryResponse::Search(res))
             }
-            QueryRequest::SearchBatch((collection_name, request)) => {
+            QueryRequest::SearchBatchWithOptions((collection_name, request, options)) => {
+                // an empty batch has an empty result, no need to involve the collection
+                if request.searches.is_empty() {
+                    return Ok(QueryResponse::SearchBatch(vec![]));
+                }
                 let requests = request
                     .searches
                     .into_iter()
-                    .map(|req| {
+                    .map(|mut req| {
+                        merge_shard_key(&mut req, options.shard_key.clone())?;
                         let SearchRequest {
                             search_request,
                             shard_key,
@@ -88,15 +325,22 @@ impl Handler for QueryRequest {
                         let shard = shard_selector(shard_key);
                         let core_request: CoreSearchRequest = search_request.into();
 
-                        (core_request, shard)
+                        Ok((core_request, shard))
                     })
-                    .collect();
+                    .collect::<Result<_, StorageError>>()?;
 
-                let res =
-                    do_search_batch_points(toc, &collection_name, requests, None, None).await?;
+                let res = do_search_batch_points(
+                    toc,
+                    &collection_name,
+                    requests,
+                    options.consistency,
+                    options.timeout,
+                )
+                .await?;
                 Ok(QueryResponse::SearchBatch(res))
             }
-            QueryRequest::SearchGroup((collection_name, request)) => {
+            QueryRequest::SearchGroupWithOptions((collection_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
                 let SearchGroupsRequest {
                     search_group_request,
                     shard_key,
@@ -107,14 +351,15 @@ impl Handler for QueryRequest {
                     toc,
                     &collection_name,
                     search_group_request,
-                    None,
+                    options.consistency,
                     shard,
-                    None,
+                    options.timeout,
                 )
                 .await?;
                 Ok(QueryResponse::SearchGroup(res))
             }
-            QueryRequest::Recommend((collection_name, request)) => {
+            QueryRequest::RecommendWithOptions((collection_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
                 let RecommendRequest {
                     recommend_request,
                     shard_key,
@@ -122,16 +367,32 @@ impl Handler for QueryRequest {
 
                 let shard = shard_selector(shard_key);
                 let res = toc
-                    .recommend(&collection_name, recommend_request, None, shard, None)
+                    .recommend(
+                        &collection_name,
+                        recommend_request,
+                        options.consistency,
+                        shard,
+                        options.timeout,
+                    )
                     .await?;
                 Ok(QueryResponse::Recommend(res))
             }
-            QueryRequest::RecommendBatch((collection_name, request)) => {
-                let res =
-                    do_recommend_batch_points(toc, &collection_name, request, None, None).await?;
+            QueryRequest::RecommendBatchWithOptions((collection_name, mut request, options)) => {
+                for req in &mut request.searches {
+                    merge_shard_key(req, options.shard_key.clone())?;
+                }
+                let res = do_recommend_batch_points(
+                    toc,
+                    &collection_name,
+                    request,
+                    options.consistency,
+                    options.timeout,
+                )
+                .await?;
                 Ok(QueryResponse::RecommendBatch(res))
             }
-            QueryRequest::RecommendGroup((collection_name, request)) => {
+            QueryRequest::RecommendGroupWithOptions((collection_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
                 let RecommendGroupsRequest {
                     recommend_group_request,
                     shard_key,
@@ -142,13 +403,67 @@ impl Handler for QueryRequest {
                     toc,
                     &collection_name,
                     recommend_group_request,
-                    None,
+                    options.consistency,
                     shard,
-                    None,
+                    options.timeout,
                 )
                 .await?;
                 Ok(QueryResponse::RecommendGroup(res))
             }
+            QueryRequest::DiscoverWithOptions((collection_name, mut request, options)) => {
+                merge_shard_key(&mut request, options.shard_key)?;
+                let DiscoverRequest {
+                    discover_request,
+                    shard_key,
+                } = request;
+
+                let shard = shard_selector(shard_key);
+                let res = toc
+                    .discover(
+                        &collection_name,
+                        discover_request,
+                        options.consistency,
+                        shard,
+                        options.timeout,
+                    )
+                    .await?;
+                Ok(QueryResponse::Discover(res))
+            }
+            QueryRequest::DiscoverBatchWithOptions((collection_name, request, options)) => {
+                if request.searches.is_empty() {
+                    return Ok(QueryResponse::DiscoverBatch(vec![]));
+                }
+                let requests = request
+                    .searches
+                    .into_iter()
+                    .map(|mut req| {
+                        merge_shard_key(&mut req, options.shard_key.clone())?;
+                        let DiscoverRequest {
+                            discover_request,
+                            shard_key,
+                        } = req;
+                        Ok((discover_request, shard_selector(shard_key)))
+                    })
+                    .collect::<Result<_, StorageError>>()?;
+
+                let res = toc
+                    .discover_batch(
+                        &collection_name,
+                        requests,
+                        options.consistency,
+                        options.timeout,
+                    )
+                    .await?;
+                Ok(QueryResponse::DiscoverBatch(res))
+            }
+            QueryRequest::Facet((collection_name, request, options)) => {
+                let res = do_facet(toc, &collection_name, request, options).await?;
+                Ok(QueryResponse::Facet(res))
+            }
+            QueryRequest::SearchMatrix((collection_name, request, options)) => {
+                let res = do_search_matrix(toc, &collection_name, request, options).await?;
+                Ok(QueryResponse::SearchMatrix(res))
+            }
         }
     }
 }
@@ -159,6 +474,198 @@ impl From<QueryRequest> for QdrantRequest {
     }
 }
 
+/// The bundled engine has no facet index, so the matching points are scrolled with only the
+/// faceted field. Array values count once per element, like Qdrant's facets do.
+async fn do_facet(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: FacetRequest,
+    options: ReadOptions,
+) -> Result<Vec<FacetHit>, StorageError> {
+    let FacetRequest { key, filter, limit } = request;
+    let shard = shard_selector(options.shard_key);
+    let counting = count_facets(
+        toc,
+        collection_name,
+        &key,
+        filter,
+        options.consistency,
+        shard,
+    );
+    // scroll has no timeout of its own, so it applies to the whole scan
+    let counts = match options.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, counting)
+            .await
+            .map_err(|_| StorageError::Timeout {
+                description: format!("Facet counting didn't finish in {timeout:?}"),
+            })??,
+        None => counting.await?,
+    };
+
+    let mut hits: Vec<_> = counts.into_values().collect();
+    hits.sort_by(|a, b| {
+        b.count
+            .cmp(&a.count)
+            .then_with(|| a.value.to_string().cmp(&b.value.to_string()))
+    });
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// count the points per value of `key`, keyed by the JSON encoding as values aren't hashable
+async fn count_facets(
+    toc: &TableOfContent,
+    collection_name: &str,
+    key: &str,
+    filter: Option<Filter>,
+    consistency: Option<ReadConsistency>,
+    shard: ShardSelectorInternal,
+) -> Result<HashMap<String, FacetHit>, StorageError> {
+    let mut counts: HashMap<String, FacetHit> = HashMap::new();
+    let mut offset = None;
+    loop {
+        let request = ScrollRequestInternal {
+            offset,
+            limit: Some(FACET_SCROLL_BATCH),
+            filter: filter.clone(),
+            with_payload: Some(WithPayloadInterface::Fields(vec![key.to_string()])),
+            with_vector: WithVector::Bool(false),
+        };
+        let res = toc
+            .scroll(collection_name, request, consistency, shard.clone())
+            .await?;
+        for record in res.points {
+            let values = match record.payload.as_ref().and_then(|p| p.0.get(key)) {
+                Some(Value::Array(values)) => values.clone(),
+                Some(value) => vec![value.clone()],
+                None => continue,
+            };
+            let mut seen = HashSet::new();
+            for value in values.into_iter().filter(is_facet_value) {
+                let encoded = value.to_string();
+                if seen.insert(encoded.clone()) {
+                    counts
+                        .entry(encoded)
+                        .or_insert(FacetHit { value, count: 0 })
+                        .count += 1;
+                }
+            }
+        }
+        match res.next_page_offset {
+            Some(next) => offset = Some(next),
+            None => return Ok(counts),
+        }
+    }
+}
+
+/// The bundled engine has no search matrix, so the sample is scrolled with its vectors and
+/// each of them is searched among the sample. The sample is the first matching points in id
+/// order rather than a random one.
+async fn do_search_matrix(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: SearchMatrixRequest,
+    options: ReadOptions,
+) -> Result<SearchMatrixResult, StorageError> {
+    let SearchMatrixRequest {
+        filter,
+        sample,
+        limit,
+        using,
+    } = request;
+    let shard = shard_selector(options.shard_key);
+    let with_vector = match &using {
+        Some(name) => WithVector::Selector(vec![name.clone()]),
+        None => WithVector::Bool(true),
+    };
+    let request = ScrollRequestInternal {
+        offset: None,
+        limit: Some(sample),
+        filter,
+        with_payload: Some(WithPayloadInterface::Bool(false)),
+        with_vector,
+    };
+    let points = toc
+        .scroll(collection_name, request, options.consistency, shard.clone())
+        .await?
+        .points;
+
+    let ids: Vec<_> = points.iter().map(|p| p.id).collect();
+    if limit == 0 || ids.len() < 2 {
+        return Ok(SearchMatrixResult { ids, pairs: vec![] });
+    }
+    let among_sample = Filter::new_must(Condition::HasId(HasIdCondition {
+        has_id: ids.iter().copied().collect(),
+    }));
+    let requests = points
+        .into_iter()
+        .map(|point| {
+            let vector = match (point.vector, &using) {
+                (Some(VectorStruct::Single(vector)), None) => NamedVectorStruct::Default(vector),
+                (Some(VectorStruct::Multi(mut vectors)), Some(name)) => {
+                    match vectors.remove(name) {
+                        Some(Vector::Dense(vector)) => NamedVectorStruct::Dense(NamedVector {
+                            name: name.clone(),
+                            vector,
+                        }),
+                        _ => return Err(missing_vector(point.id, &using)),
+                    }
+                }
+                _ => return Err(missing_vector(point.id, &using)),
+            };
+            let request = SearchRequestInternal {
+                vector,
+                filter: Some(among_sample.clone()),
+                params: None,
+                // the point itself is always its nearest neighbour
+                limit: limit + 1,
+                offset: None,
+                with_payload: None,
+                with_vector: None,
+                score_threshold: None,
+            };
+            Ok((request.into(), shard.clone()))
+        })
+        .collect::<Result<_, StorageError>>()?;
+    let results = do_search_batch_points(
+        toc,
+        collection_name,
+        requests,
+        options.consistency,
+        options.timeout,
+    )
+    .await?;
+
+    let pairs = ids
+        .iter()
+        .zip(results)
+        .flat_map(|(a, neighbours)| {
+            neighbours
+                .into_iter()
+                .filter(|n| n.id != *a)
+                .take(limit)
+                .map(|n| SearchMatrixPair {
+                    a: *a,
+                    b: n.id,
+                    score: n.score,
+                })
+        })
+        .collect();
+    Ok(SearchMatrixResult { ids, pairs })
+}
+
+fn missing_vector(id: PointIdType, using: &Option<String>) -> StorageError {
+    let name = using.as_deref().unwrap_or("default");
+    StorageError::BadInput {
+        description: format!("point {id} has no dense vector {name:?}"),
+    }
+}
+
+/// only keywords, numbers and booleans are counted, like the values a payload index holds
+fn is_facet_value(value: &Value) -> bool {
+    matches!(value, Value::String(_) | Value::Number(_) | Value::Bool(_))
+}
+
 async fn do_core_search_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -290,7 +797,21 @@ async fn do_recommend_batch_points(
     read_consistency: Option<ReadConsistency>,
     timeout: Option<Duration>,
 ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
-    let requests = request
+    if request.searches.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let requests = recommend_batch_requests(request);
+    toc.recommend_batch(collection_name, requests, read_consistency, timeout)
+        .await
+}
+
+/// Pairs every request of the batch with the shards its own shard key selects, so one batch
+/// can span several shards.
+fn recommend_batch_requests(
+    request: RecommendRequestBatch,
+) -> Vec<(RecommendRequestInternal, ShardSelectorInternal)> {
+    request
         .searches
         .into_iter()
         .map(|req| {
@@ -298,8 +819,64 @@ async fn do_recommend_batch_points(
 
             (req.recommend_request, shard)
         })
-        .collect();
+        .collect()
+}
 
-    toc.recommend_batch(collection_name, requests, read_consistency, timeout)
-        .await
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RecommendRequestBuilder;
+    use segment::types::ShardKey;
+
+    fn recommend(shard_key: Option<&str>) -> RecommendRequest {
+        let builder = RecommendRequestBuilder::new().positive([1.into()]);
+        let builder = match shard_key {
+            Some(key) => builder.shard_key(ShardKeySelector::ShardKey(ShardKey::Keyword(
+                key.to_string(),
+            ))),
+            None => builder,
+        };
+        builder.build().unwrap()
+    }
+
+    #[test]
+    fn recommend_batch_keeps_the_shard_key_of_each_request() {
+        let batch = RecommendRequestBatch {
+            searches: vec![recommend(Some("a")), recommend(Some("b")), recommend(None)],
+        };
+        let shards: Vec<_> = recommend_batch_requests(batch)
+            .into_iter()
+            .map(|(_, shard)| shard)
+            .collect();
+        assert!(matches!(
+            &shards[..],
+            [
+                ShardSelectorInternal::ShardKey(ShardKey::Keyword(a)),
+                ShardSelectorInternal::ShardKey(ShardKey::Keyword(b)),
+                ShardSelectorInternal::All,
+            ] if a == "a" && b == "b"
+        ));
+    }
+
+    #[test]
+    fn recommend_batch_options_fill_in_the_missing_shard_keys() {
+        let key = ShardKeySelector::ShardKey(ShardKey::Keyword("b".to_string()));
+        let mut batch = RecommendRequestBatch {
+            searches: vec![recommend(Some("a")), recommend(None)],
+        };
+        // the first request names a different key than the options
+        assert!(merge_shard_key(&mut batch.searches[0], Some(key.clone())).is_err());
+        merge_shard_key(&mut batch.searches[1], Some(key)).unwrap();
+        let shards: Vec<_> = recommend_batch_requests(batch)
+            .into_iter()
+            .map(|(_, shard)| shard)
+            .collect();
+        assert!(matches!(
+            &shards[..],
+            [
+                ShardSelectorInternal::ShardKey(ShardKey::Keyword(a)),
+                ShardSelectorInternal::ShardKey(ShardKey::Keyword(b)),
+            ] if a == "a" && b == "b"
+        ));
+    }
 }
@@ -0,0 +1,274 @@
+use super::ColName;
+use crate::{Handler, QdrantRequest};
+use async_trait::async_trait;
+use collection::{
+    collection::Collection,
+    config::CollectionConfig,
+    operations::snapshot_ops::{
+        get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
+        SnapshotPriority,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use storage::content_manager::{
+    collection_meta_ops::{CollectionMetaOperations, CreateCollectionOperation},
+    errors::StorageError,
+    snapshots::SnapshotConfig,
+    toc::TableOfContent,
+};
+use tracing::warn;
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum SnapshotRequest {
+    /// create a snapshot of the collection
+    Create(ColName),
+    /// list snapshots of the collection
+    List(ColName),
+    /// delete snapshot with collection name and snapshot name
+    Delete((ColName, String)),
+    /// recover the collection from a snapshot file on the local filesystem
+    Recover((ColName, PathBuf, SnapshotPriority)),
+    /// create a snapshot of all collections and aliases
+    CreateFull,
+    /// list the full snapshots
+    ListFull,
+}
+
+#[derive(Debug, Serialize)]
+pub enum SnapshotResponse {
+    /// the created snapshot
+    Create(SnapshotDescription),
+    /// snapshots of the collection
+    List(Vec<SnapshotDescription>),
+    /// deletion status
+    Delete(bool),
+    /// recovery status
+    Recover(bool),
+    /// the created full snapshot
+    CreateFull(SnapshotDescription),
+    /// full snapshots of the storage
+    ListFull(Vec<SnapshotDescription>),
+}
+
+impl SnapshotRequest {
+    pub(crate) fn op_name(&self) -> &'static str {
+        match self {
+            SnapshotRequest::Create(_) => "snapshot.create",
+            SnapshotRequest::List(_) => "snapshot.list",
+            SnapshotRequest::Delete(_) => "snapshot.delete",
+            SnapshotRequest::Recover(_) => "snapshot.recover",
+            SnapshotRequest::CreateFull => "snapshot.create_full",
+            SnapshotRequest::ListFull => "snapshot.list_full",
+        }
+    }
+
+    pub(crate) fn collection_name(&self) -> Option<&str> {
+        match self {
+            SnapshotRequest::Create(name) | SnapshotRequest::List(name) => Some(name),
+            SnapshotRequest::Delete((name, _)) | SnapshotRequest::Recover((name, _, _)) => {
+                Some(name)
+            }
+            SnapshotRequest::CreateFull | SnapshotRequest::ListFull => None,
+        }
+    }
+}
+
+#[async_trait]
+impl Handler for SnapshotRequest {
+    type Response = SnapshotResponse;
+    type Error = StorageError;
+
+    async fn handle(self, toc: &TableOfContent) -> Result<Self::Response, Self::Error> {
+        match self {
+            SnapshotRequest::Create(name) => {
+                let ret = toc.create_snapshot(&name).await?;
+                Ok(SnapshotResponse::Create(ret))
+            }
+            SnapshotRequest::List(name) => {
+                let collection = toc.get_collection(&name).await?;
+                let ret = collection.list_snapshots().await?;
+                Ok(SnapshotResponse::List(ret))
+            }
+            SnapshotRequest::Delete((name, snapshot_name)) => {
+                let collection = toc.get_collection(&name).await?;
+                let path = collection.get_snapshot_path(&snapshot_name).await?;
+                std::fs::remove_file(&path).map_err(|e| {
+                    StorageError::service_error(format!(
+                        "failed to delete snapshot {snapshot_name}: {e}"
+                    ))
+                })?;
+                Ok(SnapshotResponse::Delete(true))
+            }
+            SnapshotRequest::Recover((name, path, priority)) => {
+                let ret = do_recover_snapshot(toc, &name, &path, priority).await?;
+                Ok(SnapshotResponse::Recover(ret))
+            }
+            SnapshotRequest::CreateFull => {
+                let ret = do_create_full_snapshot(toc).await?;
+                Ok(SnapshotResponse::CreateFull(ret))
+            }
+            SnapshotRequest::ListFull => {
+                let ret = list_snapshots_in_directory(Path::new(toc.snapshots_path())).await?;
+                Ok(SnapshotResponse::ListFull(ret))
+            }
+        }
+    }
+}
+
+impl From<SnapshotRequest> for QdrantRequest {
+    fn from(req: SnapshotRequest) -> Self {
+        QdrantRequest::Snapshot(req)
+    }
+}
+
+/// Snapshot every collection, then pack the snapshots into a single archive along with the
+/// aliases, in the layout Qdrant uses to recover full snapshots.
+async fn do_create_full_snapshot(
+    toc: &TableOfContent,
+) -> Result<SnapshotDescription, StorageError> {
+    let mut collections_mapping = HashMap::new();
+    let mut collection_snapshots = Vec::new();
+    for name in toc.all_collections().await {
+        let snapshot = toc.create_snapshot(&name).await?;
+        let collection = toc.get_collection(&name).await?;
+        let path = collection.get_snapshot_path(&snapshot.name).await?;
+        collections_mapping.insert(name, snapshot.name.clone());
+        collection_snapshots.push((snapshot.name, path));
+    }
+    let collections_aliases = toc
+        .list_aliases()
+        .await?
+        .into_iter()
+        .map(|alias| (alias.alias_name, alias.collection_name))
+        .collect();
+    let config = SnapshotConfig {
+        collections_mapping,
+        collections_aliases,
+    };
+
+    // two snapshots taken in the same millisecond still get different names
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let suffix = rand::random::<u32>();
+    let path = Path::new(toc.snapshots_path())
+        .join(format!("full-snapshot-{timestamp}-{suffix:08x}.snapshot"));
+    let archive = path.clone();
+    let snapshots = collection_snapshots.clone();
+    let res =
+        tokio::task::spawn_blocking(move || write_full_snapshot(&archive, &config, &snapshots))
+            .await
+            .map_err(|e| {
+                StorageError::service_error(format!("Failed to create full snapshot: {e}"))
+            });
+
+    // the collection snapshots are only kept inside the archive
+    for (_, snapshot_path) in &collection_snapshots {
+        if let Err(e) = std::fs::remove_file(snapshot_path) {
+            warn!("Failed to remove {}: {e}", snapshot_path.display());
+        }
+    }
+    res?.map_err(|e| StorageError::service_error(format!("Failed to create full snapshot: {e}")))?;
+    Ok(get_snapshot_description(&path).await?)
+}
+
+fn write_full_snapshot(
+    path: &Path,
+    config: &SnapshotConfig,
+    snapshots: &[(String, PathBuf)],
+) -> std::io::Result<()> {
+    let mut builder = tar::Builder::new(File::create(path)?);
+    for (name, snapshot_path) in snapshots {
+        builder.append_path_with_name(snapshot_path, name)?;
+    }
+    let config = serde_json::to_vec(config)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(config.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, "config.json", config.as_slice())?;
+    builder.finish()
+}
+
+/// Unpack the snapshot next to the other snapshots, create the collection from its config if
+/// needed, then replace the local shards with the ones in the snapshot.
+///
+/// There is a single replica of every shard in embedded mode, so the priority only decides
+/// anything in a distributed deployment.
+async fn do_recover_snapshot(
+    toc: &TableOfContent,
+    collection_name: &str,
+    snapshot_path: &Path,
+    _priority: SnapshotPriority,
+) -> Result<bool, StorageError> {
+    if !snapshot_path.is_file() {
+        return Err(StorageError::BadInput {
+            description: format!("Snapshot file {} does not exist", snapshot_path.display()),
+        });
+    }
+
+    let tmp_dir = Path::new(toc.snapshots_path()).join(format!(
+        "{collection_name}-recovery-{:x}",
+        rand::random::<u64>()
+    ));
+    let res = recover_from_dir(toc, collection_name, snapshot_path, &tmp_dir).await;
+    if let Err(e) = std::fs::remove_dir_all(&tmp_dir) {
+        warn!("Failed to remove {}: {e}", tmp_dir.display());
+    }
+    res
+}
+
+async fn recover_from_dir(
+    toc: &TableOfContent,
+    collection_name: &str,
+    snapshot_path: &Path,
+    tmp_dir: &Path,
+) -> Result<bool, StorageError> {
+    let (path, dir) = (snapshot_path.to_owned(), tmp_dir.to_owned());
+    let (peer_id, is_distributed) = (toc.this_peer_id, toc.is_distributed());
+    tokio::task::spawn_blocking(move || {
+        Collection::restore_snapshot(&path, &dir, peer_id, is_distributed)
+    })
+    .await
+    .map_err(|e| StorageError::service_error(format!("Failed to restore snapshot: {e}")))??;
+
+    let snapshot_config = CollectionConfig::load(tmp_dir)?;
+    match toc.get_collection(collection_name).await {
+        // the shards are replaced as they are, so they must hold the same vectors
+        Ok(collection) => {
+            let vectors = collection.state().await.config.params.vectors;
+            if vectors != snapshot_config.params.vectors {
+                return Err(StorageError::BadInput {
+                    description: format!(
+                        "Vectors config of the snapshot doesn't match the one of collection {collection_name}"
+                    ),
+                });
+            }
+        }
+        Err(_) => {
+            let op = CollectionMetaOperations::CreateCollection(CreateCollectionOperation::new(
+                collection_name.to_string(),
+                snapshot_config.into(),
+            ));
+            toc.perform_collection_meta_op(op).await?;
+        }
+    }
+
+    let collection = toc.get_collection(collection_name).await?;
+    let shard_ids: Vec<_> = collection.state().await.shards.into_keys().collect();
+    for shard_id in shard_ids {
+        // shards are stored in a directory named after their id
+        let shard_path = tmp_dir.join(shard_id.to_string());
+        collection
+            .recover_local_shard_from(&shard_path, shard_id)
+            .await?;
+    }
+    Ok(true)
+}
@@ -0,0 +1,439 @@
+//! A local HTTP server following the paths and envelopes of the Qdrant REST API.
+
+use crate::{
+    CollectionRequest, PointsRequest, QdrantClient, QdrantError, QdrantRequest, QueryRequest,
+    ReadOptions, WriteOptions,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{delete, get, post, put},
+    Json, Router,
+};
+use collection::operations::{
+    payload_ops::{DeletePayload, SetPayload},
+    point_ops::{PointInsertOperations, PointsSelector, WriteOrdering},
+    types::{
+        CountRequest, DiscoverRequest, PointRequest, RecommendGroupsRequest, RecommendRequest,
+        RecommendRequestBatch, ScrollRequest, SearchGroupsRequest, SearchRequest,
+        SearchRequestBatch,
+    },
+    vector_ops::{DeleteVectors, UpdateVectors},
+    CreateFieldIndex,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    io,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage::content_manager::collection_meta_ops::{CreateCollection, UpdateCollection};
+
+type Client = State<Arc<QdrantClient>>;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct WriteParams {
+    wait: bool,
+    ordering: WriteOrdering,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ReadParams {
+    /// in seconds
+    timeout: Option<u64>,
+}
+
+impl From<WriteParams> for WriteOptions {
+    fn from(params: WriteParams) -> Self {
+        WriteOptions {
+            wait: params.wait,
+            ordering: params.ordering,
+            shard_key: None,
+        }
+    }
+}
+
+impl From<ReadParams> for ReadOptions {
+    fn from(params: ReadParams) -> Self {
+        ReadOptions {
+            timeout: params.timeout.map(Duration::from_secs),
+            ..Default::default()
+        }
+    }
+}
+
+/// Serve the collection and point APIs of `client` on `addr` until the server fails.
+///
+/// Requests go through [`QdrantClient`] like any other caller, so the server can run next to
+/// in-process use of the same client.
+pub async fn serve_rest(client: Arc<QdrantClient>, addr: SocketAddr) -> Result<(), QdrantError> {
+    let collection = get(get_collection)
+        .put(create_collection)
+        .patch(update_collection)
+        .delete(delete_collection);
+    let app = Router::new()
+        .route("/collections", get(list_collections))
+        .route("/collections/:name", collection)
+        .route("/collections/:name/index", put(create_field_index))
+        .route(
+            "/collections/:name/index/:field",
+            delete(delete_field_index),
+        )
+        .route(
+            "/collections/:name/points",
+            post(get_points).put(upsert_points),
+        )
+        .route("/collections/:name/points/delete", post(delete_points))
+        .route("/collections/:name/points/vectors", put(update_vectors))
+        .route(
+            "/collections/:name/points/vectors/delete",
+            post(delete_vectors),
+        )
+        .route(
+            "/collections/:name/points/payload",
+            post(set_payload).put(overwrite_payload),
+        )
+        .route(
+            "/collections/:name/points/payload/delete",
+            post(delete_payload),
+        )
+        .route(
+            "/collections/:name/points/payload/clear",
+            post(clear_payload),
+        )
+        .route("/collections/:name/points/scroll", post(scroll_points))
+        .route("/collections/:name/points/count", post(count_points))
+        .route("/collections/:name/points/search", post(search_points))
+        .route("/collections/:name/points/search/batch", post(search_batch))
+        .route(
+            "/collections/:name/points/search/groups",
+            post(search_groups),
+        )
+        .route(
+            "/collections/:name/points/recommend",
+            post(recommend_points),
+        )
+        .route(
+            "/collections/:name/points/recommend/batch",
+            post(recommend_batch),
+        )
+        .route(
+            "/collections/:name/points/recommend/groups",
+            post(recommend_groups),
+        )
+        .route("/collections/:name/points/discover", post(discover_points))
+        .with_state(client);
+
+    axum::Server::try_bind(&addr)
+        .map_err(into_io_error)?
+        .serve(app.into_make_service())
+        .await
+        .map_err(into_io_error)
+}
+
+async fn list_collections(State(client): Client) -> Response {
+    let start = Instant::now();
+    match client.list_collections().await {
+        Ok(names) => {
+            let collections: Vec<_> = names.iter().map(|name| json!({ "name": name })).collect();
+            ok(json!({ "collections": collections }), start)
+        }
+        Err(e) => error(e, start),
+    }
+}
+
+async fn get_collection(State(client): Client, Path(name): Path<String>) -> Response {
+    reply(&client, CollectionRequest::Get(name).into()).await
+}
+
+async fn create_collection(
+    State(client): Client,
+    Path(name): Path<String>,
+    Json(data): Json<CreateCollection>,
+) -> Response {
+    reply(&client, CollectionRequest::Create((name, data)).into()).await
+}
+
+async fn update_collection(
+    State(client): Client,
+    Path(name): Path<String>,
+    Json(data): Json<UpdateCollection>,
+) -> Response {
+    reply(&client, CollectionRequest::Update((name, data)).into()).await
+}
+
+async fn delete_collection(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+) -> Response {
+    reply(
+        &client,
+        CollectionRequest::DeleteWithOptions((name, params.into())).into(),
+    )
+    .await
+}
+
+async fn create_field_index(
+    State(client): Client,
+    Path(name): Path<String>,
+    Json(data): Json<CreateFieldIndex>,
+) -> Response {
+    reply(
+        &client,
+        CollectionRequest::CreateFieldIndex((name, data)).into(),
+    )
+    .await
+}
+
+async fn delete_field_index(
+    State(client): Client,
+    Path((name, field)): Path<(String, String)>,
+) -> Response {
+    reply(
+        &client,
+        CollectionRequest::DeleteFieldIndex((name, field)).into(),
+    )
+    .await
+}
+
+async fn get_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<PointRequest>,
+) -> Response {
+    reply(
+        &client,
+        PointsRequest::GetWithOptions((name, data, params.into())).into(),
+    )
+    .await
+}
+
+async fn upsert_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<PointInsertOperations>,
+) -> Response {
+    reply(
+        &client,
+        PointsRequest::UpsertWithOptions((name, data, params.into())).into(),
+    )
+    .await
+}
+
+async fn delete_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<PointsSelector>,
+) -> Response {
+    reply(
+        &client,
+        PointsRequest::DeleteWithOptions((name, data, params.into())).into(),
+    )
+    .await
+}
+
+async fn update_vectors(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<UpdateVectors>,
+) -> Response {
+    let req = PointsRequest::UpdateVectorsWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn delete_vectors(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<DeleteVectors>,
+) -> Response {
+    let req = PointsRequest::DeleteVectorsWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn set_payload(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<SetPayload>,
+) -> Response {
+    let req = PointsRequest::SetPayloadWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn overwrite_payload(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<SetPayload>,
+) -> Response {
+    let req = PointsRequest::OverwritePayloadWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn delete_payload(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<DeletePayload>,
+) -> Response {
+    let req = PointsRequest::DeletePayloadWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn clear_payload(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<WriteParams>,
+    Json(data): Json<PointsSelector>,
+) -> Response {
+    let req = PointsRequest::ClearPayloadWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn scroll_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Json(data): Json<ScrollRequest>,
+) -> Response {
+    reply(&client, PointsRequest::Scroll((name, data)).into()).await
+}
+
+async fn count_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<CountRequest>,
+) -> Response {
+    reply(
+        &client,
+        PointsRequest::CountWithOptions((name, data, params.into())).into(),
+    )
+    .await
+}
+
+async fn search_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<SearchRequest>,
+) -> Response {
+    reply(
+        &client,
+        QueryRequest::SearchWithOptions((name, data, params.into())).into(),
+    )
+    .await
+}
+
+async fn search_batch(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<SearchRequestBatch>,
+) -> Response {
+    let req = QueryRequest::SearchBatchWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn search_groups(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<SearchGroupsRequest>,
+) -> Response {
+    let req = QueryRequest::SearchGroupWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn recommend_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<RecommendRequest>,
+) -> Response {
+    let req = QueryRequest::RecommendWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn recommend_batch(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<RecommendRequestBatch>,
+) -> Response {
+    let req = QueryRequest::RecommendBatchWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn recommend_groups(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<RecommendGroupsRequest>,
+) -> Response {
+    let req = QueryRequest::RecommendGroupWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+async fn discover_points(
+    State(client): Client,
+    Path(name): Path<String>,
+    Query(params): Query<ReadParams>,
+    Json(data): Json<DiscoverRequest>,
+) -> Response {
+    let req = QueryRequest::DiscoverWithOptions((name, data, params.into()));
+    reply(&client, req.into()).await
+}
+
+/// Dispatch the request and wrap its result into the Qdrant response envelope.
+async fn reply(client: &QdrantClient, req: QdrantRequest) -> Response {
+    let start = Instant::now();
+    let res = client
+        .dispatch(req)
+        .await
+        .and_then(|res| serde_json::to_value(res).map_err(QdrantError::from));
+    match res {
+        // strip the variants of `QdrantResponse` and of the nested response
+        Ok(value) => ok(unwrap_variant(unwrap_variant(value)), start),
+        Err(e) => error(e, start),
+    }
+}
+
+fn ok(result: Value, start: Instant) -> Response {
+    let body = json!({
+        "result": result,
+        "status": "ok",
+        "time": start.elapsed().as_secs_f64(),
+    });
+    Json(body).into_response()
+}
+
+fn error(e: QdrantError, start: Instant) -> Response {
+    let body = json!({
+        "status": { "error": e.to_string() },
+        "time": start.elapsed().as_secs_f64(),
+    });
+    (StatusCode::from(&e), Json(body)).into_response()
+}
+
+/// `{"Variant": value}` to `value`
+fn unwrap_variant(value: Value) -> Value {
+    match value {
+        Value::Object(map) if map.len() == 1 => map.into_iter().next().unwrap().1,
+        value => value,
+    }
+}
+
+fn into_io_error(e: impl std::error::Error + Send + Sync + 'static) -> QdrantError {
+    QdrantError::Io(io::Error::other(e))
+}
@@ -0,0 +1,47 @@
+use crate::{QdrantClient, QdrantError};
+use rand::Rng;
+use std::{future::Future, time::Duration};
+use tracing::debug;
+
+/// upper bound of the delay between two attempts
+const MAX_RETRY_BACKOFF: Duration = Duration::from_secs(5);
+
+impl QdrantClient {
+    /// Run `f` up to `max_attempts` times, until it succeeds or fails with an error that
+    /// isn't transient, see [`QdrantError::is_transient`].
+    ///
+    /// The delay between attempts starts at `backoff` and doubles each time, up to 5 seconds,
+    /// with random jitter so that concurrent callers don't retry in lockstep. The last error
+    /// is returned once the attempts are exhausted.
+    ///
+    /// ```ignore
+    /// let res = client
+    ///     .with_retry(5, Duration::from_millis(50), |c| c.upsert_points("docs", points.clone()))
+    ///     .await?;
+    /// ```
+    pub async fn with_retry<'a, T, F, Fut>(
+        &'a self,
+        max_attempts: usize,
+        backoff: Duration,
+        mut f: F,
+    ) -> Result<T, QdrantError>
+    where
+        F: FnMut(&'a Self) -> Fut,
+        Fut: Future<Output = Result<T, QdrantError>> + 'a,
+    {
+        let mut backoff = backoff;
+        let mut attempt = 1;
+        loop {
+            match f(self).await {
+                Err(e) if attempt < max_attempts && e.is_transient() => {
+                    let delay = backoff.mul_f64(rand::thread_rng().gen_range(0.5..=1.0));
+                    debug!("Attempt {attempt} failed, retrying in {delay:?}: {e}");
+                    tokio::time::sleep(delay).await;
+                    backoff = (backoff * 2).min(MAX_RETRY_BACKOFF);
+                    attempt += 1;
+                }
+                res => return res,
+            }
+        }
+    }
+}
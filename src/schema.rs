@@ -0,0 +1,102 @@
+use crate::QdrantError;
+use segment::types::{Payload, PointIdType};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Type of a payload field, checked against the JSON value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FieldType {
+    Keyword,
+    Integer,
+    Float,
+    Bool,
+    Object,
+    Array,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldSchema {
+    pub field_type: FieldType,
+    pub required: bool,
+}
+
+/// Client-side payload schema of a collection.
+///
+/// Qdrant itself is schemaless, so this is only enforced by the client before upserting.
+/// Only top-level fields are checked, and fields not in the schema are allowed.
+///
+/// ```ignore
+/// let schema = PayloadSchema::default()
+///     .required("title", FieldType::Keyword)
+///     .optional("year", FieldType::Integer);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayloadSchema {
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+impl FieldType {
+    fn matches(&self, value: &Value) -> bool {
+        match self {
+            FieldType::Keyword => value.is_string(),
+            FieldType::Integer => value.is_i64() || value.is_u64(),
+            FieldType::Float => value.is_number(),
+            FieldType::Bool => value.is_boolean(),
+            FieldType::Object => value.is_object(),
+            FieldType::Array => value.is_array(),
+        }
+    }
+}
+
+impl PayloadSchema {
+    /// a field that must be present, and not null
+    pub fn required(mut self, name: impl Into<String>, field_type: FieldType) -> Self {
+        let schema = FieldSchema {
+            field_type,
+            required: true,
+        };
+        self.fields.insert(name.into(), schema);
+        self
+    }
+
+    /// a field that may be missing or null, but must have the given type otherwise
+    pub fn optional(mut self, name: impl Into<String>, field_type: FieldType) -> Self {
+        let schema = FieldSchema {
+            field_type,
+            required: false,
+        };
+        self.fields.insert(name.into(), schema);
+        self
+    }
+
+    /// check the payload of a point, reporting the first offending field
+    pub fn validate(
+        &self,
+        point_id: &PointIdType,
+        payload: Option<&Payload>,
+    ) -> Result<(), QdrantError> {
+        let violation = |field: &str, reason: String| QdrantError::SchemaViolation {
+            point_id: point_id.to_string(),
+            field: field.to_string(),
+            reason,
+        };
+
+        for (name, schema) in &self.fields {
+            match payload.and_then(|p| p.0.get(name)) {
+                None | Some(Value::Null) if schema.required => {
+                    return Err(violation(name, "required field is missing".to_string()));
+                }
+                None | Some(Value::Null) => {}
+                Some(value) if !schema.field_type.matches(value) => {
+                    return Err(violation(
+                        name,
+                        format!("expected {:?}, got {value}", schema.field_type),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use crate::ColName;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+/// A token returned for an acknowledged write.
+///
+/// Pass it to `QdrantClient::wait_for_receipt` before a read to make sure the
+/// read observes the write, without forcing `wait = true` on every update.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteReceipt {
+    /// collection the write was applied to
+    pub collection_name: ColName,
+    /// operation id assigned by the engine, if any
+    pub operation_id: Option<u64>,
+    /// client side clock value at the time the write was acknowledged
+    pub clock: u64,
+}
+
+/// Tracks which receipts are already known to be visible, per collection.
+#[derive(Debug, Default)]
+pub(crate) struct SessionClock {
+    clock: AtomicU64,
+    synced: Mutex<HashMap<ColName, u64>>,
+}
+
+impl SessionClock {
+    /// issue a receipt for a write that has just been acknowledged
+    pub(crate) fn issue(
+        &self,
+        collection_name: ColName,
+        operation_id: Option<u64>,
+    ) -> WriteReceipt {
+        let clock = self.clock.fetch_add(1, Ordering::SeqCst) + 1;
+        WriteReceipt {
+            collection_name,
+            operation_id,
+            clock,
+        }
+    }
+
+    /// current clock value, every receipt issued so far is at or below it
+    pub(crate) fn now(&self) -> u64 {
+        self.clock.load(Ordering::SeqCst)
+    }
+
+    /// whether a previous sync already covered the receipt
+    pub(crate) fn is_synced(&self, receipt: &WriteReceipt) -> bool {
+        let synced = self.synced.lock().unwrap();
+        synced
+            .get(&receipt.collection_name)
+            .is_some_and(|clock| *clock >= receipt.clock)
+    }
+
+    /// record that all receipts up to `clock` are visible in the collection
+    pub(crate) fn mark_synced(&self, collection_name: &str, clock: u64) {
+        let mut synced = self.synced.lock().unwrap();
+        let entry = synced.entry(collection_name.to_string()).or_default();
+        *entry = (*entry).max(clock);
+    }
+}
@@ -0,0 +1,120 @@
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// number of most recent calls kept per operation
+const SAMPLES_PER_OP: usize = 256;
+/// throughput is computed over the calls completed in this window
+const RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Rolling latency and throughput per operation, recorded by the worker for every request.
+#[derive(Debug, Default)]
+pub(crate) struct StatsSampler {
+    ops: Mutex<HashMap<&'static str, OpSamples>>,
+    // requests slower than this are logged, 0 when off
+    slow_threshold_ms: AtomicU64,
+}
+
+#[derive(Debug, Default)]
+struct OpSamples {
+    total: u64,
+    // (completed at, latency), oldest first
+    samples: VecDeque<(Instant, Duration)>,
+}
+
+/// Point-in-time view of the operations handled by the instance.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatsSnapshot {
+    pub ops: Vec<OpStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpStats {
+    /// operation name, e.g. `points.upsert`
+    pub op: &'static str,
+    /// number of calls since the instance started
+    pub total: u64,
+    /// calls per second over the last 10 seconds
+    pub ops_per_sec: f64,
+    /// median latency of the recent calls
+    pub p50: Duration,
+    /// 95th percentile latency of the recent calls
+    pub p95: Duration,
+}
+
+impl StatsSampler {
+    pub(crate) fn new(slow_threshold: Option<Duration>) -> Self {
+        let sampler = Self::default();
+        sampler.set_slow_threshold(slow_threshold);
+        sampler
+    }
+
+    /// `None` or a zero threshold turns the slow query log off, a sub-millisecond one rounds up
+    pub(crate) fn set_slow_threshold(&self, threshold: Option<Duration>) {
+        let ms = match threshold {
+            Some(t) if !t.is_zero() => (t.as_millis() as u64).max(1),
+            _ => 0,
+        };
+        self.slow_threshold_ms.store(ms, Ordering::Relaxed);
+    }
+
+    pub(crate) fn is_slow(&self, latency: Duration) -> bool {
+        let ms = self.slow_threshold_ms.load(Ordering::Relaxed);
+        ms > 0 && latency > Duration::from_millis(ms)
+    }
+
+    pub(crate) fn record(&self, op: &'static str, latency: Duration) {
+        let mut ops = self.ops.lock().unwrap();
+        let samples = ops.entry(op).or_default();
+        samples.total += 1;
+        if samples.samples.len() == SAMPLES_PER_OP {
+            samples.samples.pop_front();
+        }
+        samples.samples.push_back((Instant::now(), latency));
+    }
+
+    pub(crate) fn snapshot(&self) -> StatsSnapshot {
+        let now = Instant::now();
+        let ops = self.ops.lock().unwrap();
+        let mut ops: Vec<_> = ops
+            .iter()
+            .map(|(op, samples)| samples.stats(op, now))
+            .collect();
+        ops.sort_by_key(|s| s.op);
+        StatsSnapshot { ops }
+    }
+}
+
+impl OpSamples {
+    fn stats(&self, op: &'static str, now: Instant) -> OpStats {
+        let recent = self
+            .samples
+            .iter()
+            .filter(|(at, _)| now.duration_since(*at) <= RATE_WINDOW)
+            .count();
+        let mut latencies: Vec<_> = self.samples.iter().map(|(_, d)| *d).collect();
+        latencies.sort_unstable();
+
+        OpStats {
+            op,
+            total: self.total,
+            ops_per_sec: recent as f64 / RATE_WINDOW.as_secs_f64(),
+            p50: percentile(&latencies, 50),
+            p95: percentile(&latencies, 95),
+        }
+    }
+}
+
+fn percentile(sorted: &[Duration], p: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let idx = (sorted.len() * p).div_ceil(100).saturating_sub(1);
+    sorted[idx]
+}
@@ -0,0 +1,77 @@
+use crate::{QdrantClient, QdrantError, ScrollRequestBuilder};
+use collection::operations::types::{Record, SearchRequest};
+use futures::{stream, Stream, StreamExt};
+use segment::types::{Filter, PointIdType, ScoredPoint};
+
+/// number of points fetched per scroll request
+const SCROLL_PAGE_SIZE: usize = 256;
+
+enum ScrollState {
+    /// fetch the page starting at the offset, `None` for the first page
+    Next(Option<PointIdType>),
+    Done,
+}
+
+impl QdrantClient {
+    /// Stream every point of the collection matching the filter, with payload, in id order.
+    ///
+    /// Pages are fetched lazily as the stream is polled, so a slow consumer holds back the
+    /// scrolling. The stream ends after the first error.
+    pub fn scroll_stream(
+        &self,
+        collection_name: impl Into<String>,
+        filter: Option<Filter>,
+    ) -> impl Stream<Item = Result<Record, QdrantError>> + '_ {
+        let collection_name = collection_name.into();
+        let pages = stream::unfold(ScrollState::Next(None), move |state| {
+            let collection_name = collection_name.clone();
+            let filter = filter.clone();
+            async move {
+                let ScrollState::Next(offset) = state else {
+                    return None;
+                };
+                let mut builder = ScrollRequestBuilder::new().limit(SCROLL_PAGE_SIZE);
+                if let Some(offset) = offset {
+                    builder = builder.offset(offset);
+                }
+                if let Some(filter) = filter {
+                    builder = builder.filter(filter);
+                }
+                match self.scroll_points(collection_name, builder.build()).await {
+                    Ok(page) => {
+                        let next = match page.next_page_offset {
+                            Some(offset) => ScrollState::Next(Some(offset)),
+                            None => ScrollState::Done,
+                        };
+                        let records: Vec<_> = page.points.into_iter().map(Ok).collect();
+                        Some((records, next))
+                    }
+                    Err(e) => Some((vec![Err(e)], ScrollState::Done)),
+                }
+            }
+        });
+        pages.flat_map(stream::iter)
+    }
+
+    /// Run the searches of a stream, with up to `max_in_flight` of them in flight at once.
+    ///
+    /// Results are yielded as soon as each search is done, together with the index of its
+    /// request in `requests`, so a slow search doesn't hold back the others. Requests are only
+    /// pulled from `requests` as slots free up, so they don't have to be collected upfront
+    /// like for `search_points_batch`. An error only fails its own search.
+    pub fn search_points_stream<'a>(
+        &'a self,
+        collection_name: impl Into<String>,
+        requests: impl Stream<Item = SearchRequest> + 'a,
+        max_in_flight: usize,
+    ) -> impl Stream<Item = (usize, Result<Vec<ScoredPoint>, QdrantError>)> + 'a {
+        let collection_name = collection_name.into();
+        requests
+            .enumerate()
+            .map(move |(index, request)| {
+                let search = self.search_points(collection_name.clone(), request);
+                async move { (index, search.await) }
+            })
+            .buffer_unordered(max_in_flight.max(1))
+    }
+}
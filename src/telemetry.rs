@@ -0,0 +1,62 @@
+use crate::StatsSnapshot;
+use collection::telemetry::{CollectionTelemetry, CollectionsAggregatedTelemetry};
+use serde::Serialize;
+use std::{env, thread};
+use storage::content_manager::toc::TableOfContent;
+
+/// Telemetry collected by the ToC and the client, read locally. Nothing is sent anywhere.
+///
+/// Unlike the Qdrant server, memory and disk usage aren't reported: the server reads them in
+/// its binary, not in the storage crates embedded here.
+#[derive(Debug, Clone, Serialize)]
+pub struct TelemetryData {
+    /// the machine the instance runs on
+    pub system: SystemTelemetry,
+    /// throughput and latency per operation, as in [`QdrantClient::stats_snapshot`]
+    ///
+    /// [`QdrantClient::stats_snapshot`]: crate::QdrantClient::stats_snapshot
+    pub requests: StatsSnapshot,
+    /// vectors, optimizer status and params per collection
+    pub collections: Vec<CollectionsAggregatedTelemetry>,
+    /// shards, segments and request stats per collection, only collected at level 1 and above
+    pub details: Option<Vec<CollectionTelemetry>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SystemTelemetry {
+    /// version of this crate
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// number of CPUs available to the process, if known
+    pub cpu_count: Option<usize>,
+}
+
+impl TelemetryData {
+    /// the request stats are kept by the client, which fills them in
+    pub(crate) async fn collect(toc: &TableOfContent, level: usize) -> Self {
+        let collections = toc.get_aggregated_telemetry_data().await;
+        let details = if level >= 1 {
+            Some(toc.get_telemetry_data().await)
+        } else {
+            None
+        };
+        Self {
+            system: SystemTelemetry::collect(),
+            requests: StatsSnapshot::default(),
+            collections,
+            details,
+        }
+    }
+}
+
+impl SystemTelemetry {
+    fn collect() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            os: env::consts::OS,
+            arch: env::consts::ARCH,
+            cpu_count: thread::available_parallelism().ok().map(|n| n.get()),
+        }
+    }
+}
@@ -0,0 +1,190 @@
+use crate::{
+    client::send_request, ColName, PointsRequest, PointsResponse, QdrantError, QdrantMsg,
+    QdrantResponse,
+};
+use chrono::{DateTime, Utc};
+use collection::operations::{
+    point_ops::{FilterSelector, PointIdsList, PointsSelector},
+    types::{CountRequest, CountRequestInternal, ScrollRequest, ScrollRequestInternal},
+};
+use segment::types::{
+    Condition, FieldCondition, Filter, IsEmptyCondition, PayloadField, Range, WithPayloadInterface,
+    WithVector,
+};
+use std::{
+    io,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    sync::mpsc::{Sender, WeakSender},
+    task::JoinHandle,
+    time::{self, MissedTickBehavior},
+};
+use tracing::{debug, info, warn};
+
+const TTL_SCROLL_BATCH: usize = 1000;
+
+/// How the expiry time is stored in the TTL field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TtlFormat {
+    /// unix timestamp in seconds, expired points are deleted with a single range filter
+    #[default]
+    UnixSeconds,
+    /// RFC 3339 datetime string, e.g. `2024-01-31T12:00:00Z`. The engine can't filter on
+    /// those, so every check scrolls the points having the field
+    Datetime,
+}
+
+/// Spawn a task that purges expired points every `check_interval`.
+///
+/// The task only holds a weak sender between checks, so it never keeps the
+/// worker alive on its own and exits once the client is gone.
+pub(crate) fn spawn_ttl_task(
+    sender: WeakSender<QdrantMsg>,
+    collection_name: ColName,
+    ttl_field: String,
+    format: TtlFormat,
+    check_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = time::interval(check_interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            let Some(sender) = sender.upgrade() else {
+                debug!("Client closed, stopping TTL task for {}", collection_name);
+                break;
+            };
+            let res = match format {
+                TtlFormat::UnixSeconds => {
+                    purge_expired_timestamps(&sender, &collection_name, &ttl_field).await
+                }
+                TtlFormat::Datetime => {
+                    purge_expired_datetimes(&sender, &collection_name, &ttl_field).await
+                }
+            };
+            match res {
+                Ok(0) => debug!("Checked {} for expired points", collection_name),
+                Ok(n) => info!("Purged {} expired points from {}", n, collection_name),
+                Err(e) => warn!(
+                    "Failed to purge expired points from {}: {:?}",
+                    collection_name, e
+                ),
+            }
+        }
+    })
+}
+
+/// count, then delete the points whose timestamp is in the past with a single delete by filter
+async fn purge_expired_timestamps(
+    sender: &Sender<QdrantMsg>,
+    collection_name: &str,
+    ttl_field: &str,
+) -> Result<usize, QdrantError> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+    let range = Range {
+        lt: Some(now),
+        gt: None,
+        gte: None,
+        lte: None,
+    };
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+        ttl_field.to_string(),
+        range,
+    )));
+
+    let data = CountRequest {
+        count_request: CountRequestInternal {
+            filter: Some(filter.clone()),
+            exact: true,
+        },
+        shard_key: None,
+    };
+    let msg = PointsRequest::Count((collection_name.to_string(), data));
+    let count = match send_request(sender, msg.into()).await? {
+        QdrantResponse::Points(PointsResponse::Count(v)) => v.count,
+        res => return Err(unexpected_response(res)),
+    };
+    if count == 0 {
+        return Ok(0);
+    }
+
+    let selector = PointsSelector::FilterSelector(FilterSelector {
+        filter,
+        shard_key: None,
+    });
+    let msg = PointsRequest::Delete((collection_name.to_string(), selector));
+    send_request(sender, msg.into()).await?;
+    Ok(count)
+}
+
+/// scroll the points having the field, then delete the ones whose datetime is in the past
+async fn purge_expired_datetimes(
+    sender: &Sender<QdrantMsg>,
+    collection_name: &str,
+    ttl_field: &str,
+) -> Result<usize, QdrantError> {
+    let now = Utc::now();
+    let filter = Filter::new_must_not(Condition::IsEmpty(IsEmptyCondition {
+        is_empty: PayloadField {
+            key: ttl_field.to_string(),
+        },
+    }));
+
+    let mut expired = vec![];
+    let mut offset = None;
+    loop {
+        let data = ScrollRequest {
+            scroll_request: ScrollRequestInternal {
+                offset,
+                limit: Some(TTL_SCROLL_BATCH),
+                filter: Some(filter.clone()),
+                with_payload: Some(WithPayloadInterface::Fields(vec![ttl_field.to_string()])),
+                with_vector: WithVector::Bool(false),
+            },
+            shard_key: None,
+        };
+        let msg = PointsRequest::Scroll((collection_name.to_string(), data));
+        let res = match send_request(sender, msg.into()).await? {
+            QdrantResponse::Points(PointsResponse::Scroll(v)) => v,
+            res => return Err(unexpected_response(res)),
+        };
+        for record in res.points {
+            let expires_at = record
+                .payload
+                .as_ref()
+                .and_then(|payload| payload.0.get(ttl_field))
+                .and_then(|value| value.as_str())
+                .and_then(|value| DateTime::parse_from_rfc3339(value).ok());
+            if matches!(expires_at, Some(expires_at) if expires_at < now) {
+                expired.push(record.id);
+            }
+        }
+        match res.next_page_offset {
+            Some(next) => offset = Some(next),
+            None => break,
+        }
+    }
+    if expired.is_empty() {
+        return Ok(0);
+    }
+
+    let count = expired.len();
+    let selector = PointsSelector::PointIdsSelector(PointIdsList {
+        points: expired,
+        shard_key: None,
+    });
+    let msg = PointsRequest::Delete((collection_name.to_string(), selector));
+    send_request(sender, msg.into()).await?;
+    Ok(count)
+}
+
+fn unexpected_response(res: QdrantResponse) -> QdrantError {
+    QdrantError::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Unexpected response: {:?}", res),
+    ))
+}